@@ -1,33 +1,516 @@
+use std::collections::BTreeMap;
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
 
+#[path = "build/bindgen_config.rs"]
+mod bindgen_config;
+#[path = "build/bindings.rs"]
+mod bindings;
+#[path = "build/cc_config.rs"]
+mod cc_config;
+#[path = "build/macros.rs"]
+mod macros;
+#[path = "build/manifest.rs"]
+mod manifest;
+#[path = "build/safe_wrappers.rs"]
+mod safe_wrappers;
+#[path = "build/transpile.rs"]
+mod transpile;
+
+// Shared with the library; not every item is needed here.
+#[allow(dead_code)]
+#[path = "src/toolchain.rs"]
+mod toolchain;
+
+use bindgen_config::BindgenConfig;
+use bindings::Bindings;
+use cc_config::CcConfig;
+use manifest::Manifest;
+use toolchain::{Tool, Toolchain};
+
 fn main() {
-    let src_path = Path::new("src/");
-    // Tell cargo to look for shared libraries in the specified directory
-    println!("cargo:rustc-link-search=math_ops.h");
-
-    // Tell cargo to tell rustc to link the c_src
-    // shared library.
-    println!("cargo:rustc-link-lib=c_src");
-
-    // The bindgen::Builder is the main entry point
-    // to bindgen, and lets you build up options for
-    // the resulting bindings.
-    let bindings = bindgen::Builder::default()
-        // The input header we would like to generate
-        // bindings for.
-        .header(src_path.join("wrapper.h").to_str().unwrap())
-        // Tell cargo to invalidate the built crate whenever any of the
-        // included header files changed.
-        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
-        // Finish the builder and generate the bindings.
-        .generate()
-        // Unwrap the Result and panic on failure.
-        .expect("Unable to generate bindings");
-
-    // Write the bindings to the $OUT_DIR/bindings.rs file.
+    let c_src_path = Path::new("c_src/");
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("Couldn't write bindings!");
+    let lto_mode = LtoMode::from_env();
+    let c_opt_level = c_opt_level();
+
+    let lto_tools = (lto_mode == LtoMode::CrossLanguage).then(CrossLangTools::find);
+    let rustflags = lto_mode.rustflags(lto_tools.as_ref());
+    check_rustc_flags(lto_mode, &rustflags, lto_tools.as_ref());
+    let config = BindgenConfig::load(Path::new("ir_bindgen.toml"));
+    let headers = find_files(c_src_path, "h");
+    let macros = macros::read(c_src_path, &headers, &out_path);
+    let wrapper_path = generate_wrapper_header(c_src_path, &macros, &out_path);
+
+    // Machines without libclang can still build from the committed copy
+    // of the bindings; every build with libclang checks that copy, with or
+    // without the feature, so it can't drift from the headers.
+    let pregenerated = env::var_os("CARGO_FEATURE_PREGENERATED_BINDINGS").is_some();
+    let libclang = clang_sys::load().is_ok();
+    let bindings = match (libclang, pregenerated) {
+        (true, _) => {
+            let bindings = Bindings::generate(
+                &config,
+                c_src_path,
+                &headers,
+                &macros,
+                &wrapper_path,
+                &out_path,
+            );
+            macros::report_lost(c_src_path, &macros, &bindings.text);
+            bindings.check_pregenerated(&wrapper_path);
+            bindings
+        }
+        (false, true) => Bindings::pregenerated(&wrapper_path, &out_path),
+        (false, false) => panic!(
+            "libclang is needed to generate the bindings; set LIBCLANG_PATH, or build with \
+             `--features pregenerated-bindings` to use the committed copy"
+        ),
+    };
+
+    safe_wrappers::generate(&bindings.text, &out_path);
+
+    let transpiled = env::var_os("CARGO_FEATURE_TRANSPILE_INLINE")
+        .is_some()
+        .then(|| {
+            if !libclang {
+                panic!("the transpile-inline feature needs libclang; set LIBCLANG_PATH");
+            }
+            transpile::transpile(c_src_path, &headers, &wrapper_path, &out_path)
+        });
+
+    // Compile the C sources and the generated shims into one archive. The
+    // shims include the wrapper header by its absolute path.
+    let sources = find_files(c_src_path, "c");
+    let cc_config = CcConfig::load(Path::new("ir_cc.toml"), c_src_path, &sources);
+    let mut build = cc::Build::new();
+    build
+        .opt_level_str(&c_opt_level)
+        .debug(env::var("DEBUG").unwrap() == "true");
+    if let Some(flag) = target_cpu_flag() {
+        build.flag(flag);
+    }
+    let mut link_args = Vec::new();
+    if let Some(tools) = &lto_tools {
+        // Emit ThinLTO bitcode instead of machine code, so the linker
+        // optimizes the C objects together with rustc's bitcode. The
+        // archive needs an LLVM-aware symbol table for lld to find them.
+        build.compiler(&tools.clang).archiver(&tools.llvm_ar);
+        link_args.push(format!("--ld-path={}", tools.lld.display()));
+    }
+    for flag in lto_mode.c_flags() {
+        build.flag(flag);
+    }
+    for flag in &cc_config.flags {
+        build.flag(flag);
+    }
+    for arg in &link_args {
+        println!("cargo:rustc-link-arg={arg}");
+    }
+    compile_c_kernels(&build, &cc_config, c_src_path, &sources, &bindings.shims);
+
+    let compiler = build.get_compiler();
+    let build_config = write_build_config(
+        &out_path,
+        lto_mode,
+        &c_opt_level,
+        &compiler,
+        &rustflags,
+        &link_args,
+    );
+
+    let file_flags = sources
+        .iter()
+        .filter_map(|source| {
+            let flags = cc_config.file_flags(c_src_path, source)?;
+            Some((source.display().to_string(), flags.to_vec()))
+        })
+        .collect();
+    let macro_functions = macros.values().filter_map(|m| m.functions.as_ref());
+    let inputs = headers
+        .iter()
+        .chain(macro_functions)
+        .chain(&sources)
+        .chain(&bindings.shims)
+        .chain([&bindings.path])
+        .chain(&transpiled)
+        .cloned()
+        .collect();
+    Manifest {
+        build_config,
+        compiler: &compiler,
+        file_flags,
+        bindgen_version: bindings.bindgen_version(),
+        bindgen_options: bindings.options,
+        libclang: libclang.then(|| bindgen::clang_version().full),
+        inputs,
+    }
+    .write(&out_path);
+}
+
+/// Compiles `sources` and `shims` with the flags of `build` into the
+/// `c_kernels` archive. Sources with flags in `ir_cc.toml` get them on top,
+/// so they are compiled on their own, and sources limited to another
+/// `target_arch` are left out.
+fn compile_c_kernels(
+    build: &cc::Build,
+    cc_config: &CcConfig,
+    c_src_path: &Path,
+    sources: &[PathBuf],
+    shims: &[PathBuf],
+) {
+    let mut objects = Vec::new();
+    let mut common = build.clone();
+    let mut common_files = shims.len();
+    common.files(shims);
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    for source in sources {
+        if !cc_config.builds_for(c_src_path, source, &target_arch) {
+            continue;
+        }
+        match cc_config.file_flags(c_src_path, source) {
+            Some(flags) => {
+                let mut file_build = build.clone();
+                file_build.file(source);
+                for flag in flags {
+                    file_build.flag(flag);
+                }
+                objects.extend(file_build.compile_intermediates());
+            }
+            None => {
+                common.file(source);
+                common_files += 1;
+            }
+        }
+    }
+    if common_files > 0 {
+        objects.extend(common.compile_intermediates());
+    }
+    if !objects.is_empty() {
+        build.clone().objects(objects).compile("c_kernels");
+    }
+}
+
+/// Maps `-C target-cpu` from RUSTFLAGS onto the C compiler, so both sides
+/// are tuned for the same CPU.
+fn target_cpu_flag() -> Option<String> {
+    let cpu = codegen_flags()
+        .into_iter()
+        .rev()
+        .find_map(|flag| flag.strip_prefix("target-cpu=").map(str::to_owned))?;
+    // x86 spells it `-march`; ARM, AArch64 and RISC-V use `-mcpu`.
+    match env::var("CARGO_CFG_TARGET_ARCH").unwrap().as_str() {
+        "x86" | "x86_64" => Some(format!("-march={cpu}")),
+        _ => Some(format!("-mcpu={cpu}")),
+    }
+}
+
+/// Writes `$OUT_DIR/wrapper.h`, an umbrella header that includes every
+/// header under `c_src`, each followed by the functions written out from its
+/// macros, and returns its path.
+fn generate_wrapper_header(
+    c_src_path: &Path,
+    macros: &BTreeMap<PathBuf, macros::HeaderMacros>,
+    out_path: &Path,
+) -> PathBuf {
+    // Watching the directory also picks up headers that are added later.
+    println!("cargo:rerun-if-changed={}", c_src_path.display());
+
+    let mut wrapper = String::from("#ifndef WRAPPER_H\n#define WRAPPER_H\n\n");
+    for (header, header_macros) in macros {
+        println!("cargo:rerun-if-changed={}", header.display());
+        let header = header.canonicalize().unwrap();
+        wrapper.push_str(&format!("#include \"{}\"\n", header.display()));
+        if let Some(functions) = &header_macros.functions {
+            wrapper.push_str(&format!("#include \"{}\"\n", functions.display()));
+        }
+    }
+    wrapper.push_str("\n#endif\n");
+
+    let wrapper_path = out_path.join("wrapper.h");
+    fs::write(&wrapper_path, wrapper).expect("Couldn't write wrapper.h!");
+    wrapper_path
+}
+
+/// Returns every file under `dir` with the given extension, sorted so the
+/// generated code does not depend on directory order.
+fn find_files(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let entries = fs::read_dir(dir).unwrap_or_else(|err| panic!("Couldn't read {dir:?}: {err}"));
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(find_files(&path, extension));
+        } else if path.extension().is_some_and(|ext| ext == extension) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+/// How the C and Rust sides are optimized together, selected with
+/// `IR_LTO_MODE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LtoMode {
+    /// No LTO at all, not even across the crate's own codegen units.
+    Off,
+    /// rustc's default: ThinLTO across the codegen units of each crate.
+    ThinLocal,
+    /// ThinLTO across all Rust crates; the C objects stay machine code.
+    Thin,
+    /// Fat LTO across all Rust crates; the C objects stay machine code.
+    Fat,
+    /// ThinLTO across Rust and C, merged by the linker.
+    CrossLanguage,
+}
+
+impl LtoMode {
+    const ALL: [LtoMode; 5] = [
+        LtoMode::Off,
+        LtoMode::ThinLocal,
+        LtoMode::Thin,
+        LtoMode::Fat,
+        LtoMode::CrossLanguage,
+    ];
+
+    /// Reads `IR_LTO_MODE`. The `cross-lang-lto` feature is a shorthand for
+    /// `IR_LTO_MODE=cross-language`, so the two must not disagree.
+    fn from_env() -> LtoMode {
+        println!("cargo:rerun-if-env-changed=IR_LTO_MODE");
+        let feature = env::var_os("CARGO_FEATURE_CROSS_LANG_LTO").is_some();
+        let mode = match env::var("IR_LTO_MODE") {
+            Ok(name) => LtoMode::ALL
+                .into_iter()
+                .find(|mode| mode.name() == name)
+                .unwrap_or_else(|| {
+                    let names: Vec<_> = LtoMode::ALL.iter().map(|mode| mode.name()).collect();
+                    panic!(
+                        "unknown IR_LTO_MODE `{name}`, expected one of: {}",
+                        names.join(", ")
+                    )
+                }),
+            Err(_) if feature => LtoMode::CrossLanguage,
+            Err(_) => LtoMode::ThinLocal,
+        };
+        if feature && mode != LtoMode::CrossLanguage {
+            panic!(
+                "the `cross-lang-lto` feature conflicts with IR_LTO_MODE={}",
+                mode.name()
+            );
+        }
+        mode
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            LtoMode::Off => "off",
+            LtoMode::ThinLocal => "thin-local",
+            LtoMode::Thin => "thin",
+            LtoMode::Fat => "fat",
+            LtoMode::CrossLanguage => "cross-language",
+        }
+    }
+
+    /// The `-C lto` value rustc has to run with, or `None` for no flag.
+    fn rustc_lto(self) -> Option<&'static str> {
+        match self {
+            LtoMode::Off => Some("off"),
+            LtoMode::ThinLocal | LtoMode::CrossLanguage => None,
+            LtoMode::Thin => Some("thin"),
+            LtoMode::Fat => Some("fat"),
+        }
+    }
+
+    /// The RUSTFLAGS that select this mode on the rustc side. A build
+    /// script cannot pass codegen flags to rustc itself. Cross-language LTO
+    /// links with the clang in `tools`.
+    fn rustflags(self, tools: Option<&CrossLangTools>) -> Vec<String> {
+        let flags: &[&str] = match self {
+            LtoMode::Off => &["-Clto=off"],
+            LtoMode::ThinLocal => &[],
+            // Cargo passes `-Cembed-bitcode=no` to crates it does not
+            // expect to be LTO'd, which rustc rejects together with `-Clto`.
+            LtoMode::Thin => &["-Clto=thin", "-Cembed-bitcode=yes"],
+            LtoMode::Fat => &["-Clto=fat", "-Cembed-bitcode=yes"],
+            LtoMode::CrossLanguage => {
+                let clang = tools.map_or("clang".into(), |tools| tools.clang.to_string_lossy());
+                return vec![
+                    "-Clinker-plugin-lto".to_owned(),
+                    format!("-Clinker={clang}"),
+                ];
+            }
+        };
+        flags.iter().map(|&flag| flag.to_owned()).collect()
+    }
+
+    fn c_flags(self) -> &'static [&'static str] {
+        match self {
+            LtoMode::CrossLanguage => &["-flto=thin"],
+            _ => &[],
+        }
+    }
+}
+
+/// Reads `IR_C_OPT`, falling back to the cargo profile's opt-level.
+fn c_opt_level() -> String {
+    println!("cargo:rerun-if-env-changed=IR_C_OPT");
+    match env::var("IR_C_OPT") {
+        Ok(level) if ["0", "1", "2", "3", "s", "z"].contains(&level.as_str()) => level,
+        Ok(level) => panic!("unknown IR_C_OPT `{level}`, expected one of: 0, 1, 2, 3, s, z"),
+        Err(_) => env::var("OPT_LEVEL").unwrap(),
+    }
+}
+
+/// Fails the build when RUSTFLAGS select a different LTO mode on the Rust
+/// side than `IR_LTO_MODE` does on the C side, or, for cross-language LTO,
+/// a linker that isn't a clang on rustc's LLVM major version.
+fn check_rustc_flags(lto_mode: LtoMode, rustflags: &[String], tools: Option<&CrossLangTools>) {
+    println!("cargo:rerun-if-env-changed=RUSTFLAGS");
+    println!("cargo:rerun-if-env-changed=CARGO_ENCODED_RUSTFLAGS");
+
+    let flags = codegen_flags();
+    // rustc accepts several spellings for the boolean settings of `-C lto`.
+    let lto = flags.iter().rev().find_map(|flag| match flag.as_str() {
+        "lto" => Some("fat"),
+        _ => match flag.strip_prefix("lto=")? {
+            "y" | "yes" | "on" | "true" | "fat" => Some("fat"),
+            "n" | "no" | "off" | "false" => Some("off"),
+            other => Some(other),
+        },
+    });
+    let linker_plugin_lto = flags.iter().any(|flag| flag == "linker-plugin-lto");
+    let expected_plugin = lto_mode == LtoMode::CrossLanguage;
+
+    if lto != lto_mode.rustc_lto() || linker_plugin_lto != expected_plugin {
+        panic!(
+            "IR_LTO_MODE={} needs rustc to run with RUSTFLAGS=\"{}\"",
+            lto_mode.name(),
+            rustflags.join(" ")
+        );
+    }
+
+    // The linker runs the LTO step, so it has to read rustc's bitcode; a
+    // `clang` on the PATH from another LLVM release silently doesn't.
+    if let Some(tools) = tools {
+        let linker = flags
+            .iter()
+            .rev()
+            .find_map(|flag| flag.strip_prefix("linker="));
+        let Some(linker) = linker else {
+            panic!(
+                "IR_LTO_MODE=cross-language needs rustc to run with RUSTFLAGS=\"{}\"",
+                rustflags.join(" ")
+            );
+        };
+        let found = toolchain::tool_version(linker);
+        if found.is_none_or(|version| version.major != tools.llvm.major) {
+            let found = found.map_or("no version".to_owned(), |version| format!("LLVM {version}"));
+            panic!(
+                "the linker `{linker}` reports {found}, but rustc uses LLVM {}; \
+                 link with a matching clang: RUSTFLAGS=\"{}\"",
+                tools.llvm,
+                rustflags.join(" ")
+            );
+        }
+    }
+}
+
+/// Writes the resolved configuration to `$OUT_DIR/build_config.json`, and
+/// as the `BUILD_CONFIG` constant to `$OUT_DIR/build_config.rs`. Returns the
+/// JSON for the manifest.
+fn write_build_config(
+    out_path: &Path,
+    lto_mode: LtoMode,
+    c_opt_level: &str,
+    compiler: &cc::Tool,
+    rustflags: &[String],
+    link_args: &[String],
+) -> serde_json::Value {
+    let c_flags: Vec<String> = compiler
+        .args()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+
+    let json = serde_json::json!({
+        "lto_mode": lto_mode.name(),
+        "c_opt_level": c_opt_level,
+        "c_compiler": compiler.path(),
+        "c_flags": c_flags,
+        "rustflags": rustflags,
+        "link_args": link_args,
+    });
+    fs::write(
+        out_path.join("build_config.json"),
+        serde_json::to_string_pretty(&json).unwrap(),
+    )
+    .expect("Couldn't write build_config.json!");
+
+    let rust = format!(
+        "pub const BUILD_CONFIG: BuildConfig = BuildConfig {{\n    \
+         lto_mode: {:?},\n    \
+         c_opt_level: {:?},\n    \
+         c_compiler: {:?},\n    \
+         c_flags: &{:?},\n    \
+         rustflags: &{:?},\n    \
+         link_args: &{:?},\n\
+         }};\n",
+        lto_mode.name(),
+        c_opt_level,
+        compiler.path().to_string_lossy(),
+        c_flags,
+        rustflags,
+        link_args,
+    );
+    fs::write(out_path.join("build_config.rs"), rust).expect("Couldn't write build_config.rs!");
+    json
+}
+
+/// The tools cross-language LTO builds with, all on rustc's LLVM major
+/// version `llvm`. Mismatched versions do not error at link time; the
+/// bitcode is just not merged.
+struct CrossLangTools {
+    llvm: toolchain::LlvmVersion,
+    clang: PathBuf,
+    llvm_ar: PathBuf,
+    lld: PathBuf,
+}
+
+impl CrossLangTools {
+    fn find() -> CrossLangTools {
+        for tool in [Tool::Clang, Tool::LlvmAr, Tool::Lld] {
+            println!("cargo:rerun-if-env-changed={}", tool.env_var());
+        }
+        let toolchain = Toolchain::from_env().unwrap_or_else(|err| panic!("{err}"));
+        let find = |tool| {
+            toolchain
+                .find(tool)
+                .unwrap_or_else(|err| panic!("cross-language LTO: {err}"))
+        };
+        CrossLangTools {
+            llvm: toolchain.llvm,
+            clang: find(Tool::Clang),
+            llvm_ar: find(Tool::LlvmAr),
+            lld: find(Tool::Lld),
+        }
+    }
+}
+
+/// Returns the value of every `-C` flag rustc is invoked with.
+fn codegen_flags() -> Vec<String> {
+    let encoded = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    let mut flags = Vec::new();
+    let mut args = encoded.split('\x1f').filter(|arg| !arg.is_empty());
+    while let Some(arg) = args.next() {
+        if arg == "-C" || arg == "--codegen" {
+            flags.extend(args.next().map(str::to_owned));
+        } else if let Some(flag) = arg.strip_prefix("-C") {
+            flags.push(flag.to_owned());
+        } else if let Some(flag) = arg.strip_prefix("--codegen=") {
+            flags.push(flag.to_owned());
+        }
+    }
+    flags
 }
@@ -0,0 +1,180 @@
+//! The bindgen settings `build.rs` reads from `ir_bindgen.toml`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bindgen::callbacks::{IntKind, ParseCallbacks};
+use bindgen::{Builder, EnumVariation, MacroTypeVariation};
+use serde::Deserialize;
+
+use crate::macros::Macro;
+
+const DERIVES: [&str; 8] = [
+    "Copy",
+    "Debug",
+    "Default",
+    "Hash",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BindgenConfig {
+    pub allowlist: Vec<String>,
+    pub blocklist: Vec<String>,
+    pub enum_style: String,
+    pub size_t_is_usize: bool,
+    pub layout_tests: bool,
+    pub derive: Vec<String>,
+    pub macro_constant_type: String,
+    pub static_fns: StaticFns,
+    /// Extra enum styles per header, keyed by its path below `c_src`.
+    #[serde(default)]
+    pub enum_style_variants: BTreeMap<String, Vec<String>>,
+    /// Groups of integer macros bound with a type of their own, as type
+    /// names mapped to the prefix of the macros.
+    #[serde(default)]
+    pub macro_groups: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StaticFns {
+    pub wrap: bool,
+    pub suffix: String,
+}
+
+impl BindgenConfig {
+    pub fn load(path: &Path) -> BindgenConfig {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let text = fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("Couldn't read {}: {err}", path.display()));
+        let config: BindgenConfig =
+            toml::from_str(&text).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+
+        if let Some(unknown) = config
+            .derive
+            .iter()
+            .find(|name| !DERIVES.contains(&name.as_str()))
+        {
+            panic!(
+                "{}: can't derive `{unknown}`, expected one of: {}",
+                path.display(),
+                DERIVES.join(", ")
+            );
+        }
+        for style in config.enum_style_variants.values().flatten() {
+            parse_enum_style(style, "enum_style_variants");
+        }
+        config.macro_constant_type();
+        config
+    }
+
+    /// Checks that every `enum_style_variants` entry names one of `headers`,
+    /// whose paths are relative to `c_src_path`.
+    pub fn check_headers(&self, c_src_path: &Path, headers: &[PathBuf]) {
+        for name in self.enum_style_variants.keys() {
+            if !headers.contains(&c_src_path.join(name)) {
+                panic!(
+                    "ir_bindgen.toml: enum_style_variants: no header `{name}` under {}",
+                    c_src_path.display()
+                );
+            }
+        }
+    }
+
+    /// The extra enum styles `header` is generated in.
+    pub fn enum_style_variants(&self, c_src_path: &Path, header: &Path) -> &[String] {
+        header
+            .strip_prefix(c_src_path)
+            .ok()
+            .and_then(|name| self.enum_style_variants.get(name.to_str()?))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Applies every setting except the allowlist and the static function
+    /// shim, which depend on the header being generated.
+    pub fn apply(&self, mut builder: Builder) -> Builder {
+        for item in &self.blocklist {
+            builder = builder.blocklist_item(item);
+        }
+        builder
+            .default_enum_style(self.enum_style())
+            .size_t_is_usize(self.size_t_is_usize)
+            .layout_tests(self.layout_tests)
+            .derive_copy(self.derives("Copy"))
+            .derive_debug(self.derives("Debug"))
+            .derive_default(self.derives("Default"))
+            .derive_hash(self.derives("Hash"))
+            .derive_partialeq(self.derives("PartialEq"))
+            .derive_eq(self.derives("Eq"))
+            .derive_partialord(self.derives("PartialOrd"))
+            .derive_ord(self.derives("Ord"))
+            .default_macro_constant_type(self.macro_constant_type())
+            .parse_callbacks(Box::new(MacroGroups(self.macro_groups.clone())))
+    }
+
+    /// The type alias of each macro group that has a member in `macros`,
+    /// for the bindings of their header.
+    pub fn macro_group_aliases(&self, macros: &[Macro]) -> Vec<String> {
+        self.macro_groups
+            .iter()
+            .filter(|(_, prefix)| {
+                macros
+                    .iter()
+                    .any(|m| m.params.is_none() && m.name.starts_with(prefix.as_str()))
+            })
+            .map(|(name, _)| format!("pub type {name} = ::std::os::raw::c_int;"))
+            .collect()
+    }
+
+    /// The suffix of the shim symbols generated for `module`.
+    pub fn static_fns_suffix(&self, module: &str) -> String {
+        self.static_fns.suffix.replace("{module}", module)
+    }
+
+    fn enum_style(&self) -> EnumVariation {
+        parse_enum_style(&self.enum_style, "enum_style")
+    }
+
+    fn macro_constant_type(&self) -> MacroTypeVariation {
+        self.macro_constant_type
+            .parse()
+            .unwrap_or_else(|err| panic!("ir_bindgen.toml: macro_constant_type: {err}"))
+    }
+
+    fn derives(&self, name: &str) -> bool {
+        self.derive.iter().any(|derive| derive == name)
+    }
+}
+
+/// Parses an enum style, e.g. `rust`, named in the `setting` of the config.
+pub fn parse_enum_style(style: &str, setting: &str) -> EnumVariation {
+    style
+        .parse()
+        .unwrap_or_else(|err| panic!("ir_bindgen.toml: {setting}: {err}"))
+}
+
+/// Types the integer macros of each group with the group's type alias
+/// instead of a plain integer.
+#[derive(Debug)]
+struct MacroGroups(BTreeMap<String, String>);
+
+impl ParseCallbacks for MacroGroups {
+    fn int_macro(&self, name: &str, _value: i64) -> Option<IntKind> {
+        let (group, _) = self
+            .0
+            .iter()
+            .find(|(_, prefix)| name.starts_with(prefix.as_str()))?;
+        // Bindgen keeps the name for as long as the build runs anyway.
+        let name: &'static str = Box::leak(group.clone().into_boxed_str());
+        Some(IntKind::Custom {
+            name,
+            is_signed: true,
+        })
+    }
+}
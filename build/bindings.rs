@@ -0,0 +1,267 @@
+//! Generates the bindings: one module per header under `c_src`, or the
+//! committed copy in `src/bindings_pregenerated.rs` where libclang is
+//! missing.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::bindgen_config::{self, BindgenConfig};
+use crate::macros::HeaderMacros;
+use crate::safe_wrappers::AnnotationCallbacks;
+
+/// The committed copy of the generated bindings.
+const PREGENERATED_RS: &str = "src/bindings_pregenerated.rs";
+/// The committed copy of the static function shims, without the line that
+/// includes the wrapper header, since its path differs between machines.
+const PREGENERATED_C: &str = "src/bindings_pregenerated.c";
+
+const HEADER: &str = "// Generated by build.rs from the headers under c_src. Do not edit.\n\n";
+
+pub struct Bindings {
+    /// The file `c_wrapper` includes.
+    pub path: PathBuf,
+    pub text: String,
+    /// The static function shims to compile.
+    pub shims: Vec<PathBuf>,
+    /// The bindgen command line equivalent of each module. Empty for the
+    /// committed copy.
+    pub options: BTreeMap<String, Vec<String>>,
+}
+
+impl Bindings {
+    /// Runs bindgen once per header and writes `$OUT_DIR/bindings.rs`.
+    ///
+    /// Each header gets its own module, so that functions with the same name
+    /// in different kernel families don't clash. Every run parses the whole
+    /// umbrella header, but only emits the items declared in its own header.
+    /// Headers in `enum_style_variants` get one more module per extra enum
+    /// style, `<module>_<style>`. The functions written out from a header's
+    /// macros go in the module of the header.
+    pub fn generate(
+        config: &BindgenConfig,
+        c_src_path: &Path,
+        headers: &[PathBuf],
+        macros: &BTreeMap<PathBuf, HeaderMacros>,
+        wrapper_path: &Path,
+        out_path: &Path,
+    ) -> Bindings {
+        let mut text = String::from(HEADER);
+        let mut shims = Vec::new();
+        let mut options = BTreeMap::new();
+        config.check_headers(c_src_path, headers);
+        let modules = headers.iter().flat_map(|header| {
+            let module = module_name(c_src_path, header);
+            let main = (header, module.clone(), None);
+            let variants = config
+                .enum_style_variants(c_src_path, header)
+                .iter()
+                .map(move |style| (header, format!("{module}_{style}"), Some(style.as_str())));
+            [main].into_iter().chain(variants)
+        });
+        for (header, module, enum_style) in modules {
+            let shim_path = out_path.join(format!("extern_{module}"));
+            let shim_source = shim_path.with_extension("c");
+            // bindgen only writes the shim when the header has static functions.
+            let _ = fs::remove_file(&shim_source);
+
+            // The bindgen::Builder is the main entry point
+            // to bindgen, and lets you build up options for
+            // the resulting bindings.
+            let mut builder = bindgen::Builder::default()
+                // The input header we would like to generate
+                // bindings for.
+                .header(wrapper_path.to_str().unwrap())
+                // Tell cargo to invalidate the built crate whenever any of the
+                // included header files changed. The wrapper itself is generated,
+                // and the headers it includes are tracked when it is written.
+                .parse_callbacks(Box::new(
+                    bindgen::CargoCallbacks::new().rerun_on_header_files(false),
+                ))
+                .parse_callbacks(Box::new(AnnotationCallbacks))
+                // The crate is on edition 2024, where the unsafe operations
+                // in helpers like `__IncompleteArrayField::as_slice` need an
+                // `unsafe` block of their own.
+                .wrap_unsafe_ops(true);
+
+            // An allowlist would also pull in matching items from the other
+            // headers, so those are blocklisted instead of allowlisting the file.
+            let header_macros = &macros[header];
+            if config.allowlist.is_empty() {
+                builder = builder.allowlist_file(header_regex(header));
+                if let Some(functions) = &header_macros.functions {
+                    builder = builder.allowlist_file(header_regex(functions));
+                }
+            } else {
+                for item in &config.allowlist {
+                    builder = builder.allowlist_item(item);
+                }
+                for other in headers.iter().filter(|other| *other != header) {
+                    builder = builder.blocklist_file(header_regex(other));
+                    if let Some(functions) = &macros[other].functions {
+                        builder = builder.blocklist_file(header_regex(functions));
+                    }
+                }
+            }
+            for alias in config.macro_group_aliases(&header_macros.macros) {
+                builder = builder.raw_line(alias);
+            }
+
+            // `static inline` functions have no symbol to link against, so
+            // ask bindgen to emit a C shim that wraps each of them in an
+            // out-of-line function, and bind to the shim instead. The suffix
+            // keeps the shim symbols of different modules apart. An enum style
+            // variant would define the same shims again, so it leaves static
+            // functions out.
+            if config.static_fns.wrap && enum_style.is_none() {
+                builder = builder
+                    .wrap_static_fns(true)
+                    .wrap_static_fns_path(&shim_path)
+                    .wrap_static_fns_suffix(config.static_fns_suffix(&module));
+            }
+
+            let mut builder = config.apply(builder);
+            if let Some(style) = enum_style {
+                builder = builder.default_enum_style(bindgen_config::parse_enum_style(
+                    style,
+                    "enum_style_variants",
+                ));
+            }
+            options.insert(module.clone(), builder.command_line_flags());
+            let bindings = builder
+                // Finish the builder and generate the bindings.
+                .generate()
+                // Unwrap the Result and panic on failure.
+                .expect("Unable to generate bindings");
+
+            // The safe wrappers are generated from this text afterwards, so
+            // that the committed copy of it gets them too.
+            // The variants declare the same C functions as the main module,
+            // with other parameter types.
+            let doc = match enum_style {
+                None => format!("/// Bindings for `{}`.\n", header.display()),
+                Some(style) => format!(
+                    "/// Bindings for `{}`, with `{style}` enums.\n\
+                     #[allow(clashing_extern_declarations)]\n",
+                    header.display()
+                ),
+            };
+            text.push_str(&format!(
+                "{doc}pub mod {module} {{\n{bindings}\n\
+                 include!(concat!(env!(\"OUT_DIR\"), \"/safe_wrappers/{module}.rs\"));\n}}\n\n"
+            ));
+            if shim_source.exists() {
+                shims.push(shim_source);
+            }
+        }
+
+        // Write the bindings to the $OUT_DIR/bindings.rs file.
+        let path = out_path.join("bindings.rs");
+        fs::write(&path, &text).expect("Couldn't write bindings!");
+        Bindings {
+            path,
+            text,
+            shims,
+            options,
+        }
+    }
+
+    /// Uses the committed copy of the bindings and the shims.
+    pub fn pregenerated(wrapper_path: &Path, out_path: &Path) -> Bindings {
+        println!("cargo:rerun-if-changed={PREGENERATED_RS}");
+        println!("cargo:rerun-if-changed={PREGENERATED_C}");
+        let path = PathBuf::from(PREGENERATED_RS);
+        let text = read(&path);
+
+        let mut shims = Vec::new();
+        let shim_bodies = read(Path::new(PREGENERATED_C));
+        if shim_bodies != HEADER {
+            let shim_source = out_path.join("extern_pregenerated.c");
+            let shim = format!("{}\n{shim_bodies}", include_line(wrapper_path));
+            fs::write(&shim_source, shim).expect("Couldn't write the static function shim!");
+            shims.push(shim_source);
+        }
+
+        Bindings {
+            path,
+            text,
+            shims,
+            options: BTreeMap::new(),
+        }
+    }
+
+    /// Fails the build if the committed copy differs from these freshly
+    /// generated bindings, or updates it if `IR_BLESS_BINDINGS` is set.
+    pub fn check_pregenerated(&self, wrapper_path: &Path) {
+        println!("cargo:rerun-if-env-changed=IR_BLESS_BINDINGS");
+        let mut shim_bodies = String::from(HEADER);
+        for shim in &self.shims {
+            let shim = read(shim);
+            let body = shim
+                .strip_prefix(&include_line(wrapper_path))
+                .unwrap_or(&shim);
+            shim_bodies.push_str(body.trim_start());
+        }
+
+        let committed_rs = fs::read_to_string(PREGENERATED_RS).unwrap_or_default();
+        let committed_c = fs::read_to_string(PREGENERATED_C).unwrap_or_default();
+        if committed_rs == self.text && committed_c == shim_bodies {
+            return;
+        }
+        if env::var_os("IR_BLESS_BINDINGS").is_some() {
+            fs::write(PREGENERATED_RS, &self.text).expect("Couldn't update the bindings!");
+            fs::write(PREGENERATED_C, shim_bodies).expect("Couldn't update the shims!");
+            println!("cargo:warning=updated {PREGENERATED_RS} and {PREGENERATED_C}");
+        } else {
+            panic!(
+                "{PREGENERATED_RS} or {PREGENERATED_C} is out of date with the headers under \
+                 c_src or ir_bindgen.toml; rebuild with IR_BLESS_BINDINGS=1 to update them"
+            );
+        }
+    }
+
+    /// The bindgen version from the comment at the top of each module.
+    pub fn bindgen_version(&self) -> Option<String> {
+        self.text
+            .split("automatically generated by rust-bindgen ")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .map(str::to_owned)
+    }
+}
+
+/// Names the binding module of a header after its path below `c_src`,
+/// e.g. `c_src/simd/avx.h` becomes `simd_avx`.
+pub fn module_name(c_src_path: &Path, header: &Path) -> String {
+    header
+        .strip_prefix(c_src_path)
+        .unwrap()
+        .with_extension("")
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// A regex that matches exactly the path of `header`, as bindgen sees it.
+fn header_regex(header: &Path) -> String {
+    let path = header.canonicalize().unwrap();
+    let mut escaped = String::new();
+    for c in path.to_string_lossy().chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The line bindgen starts each shim with.
+fn include_line(wrapper_path: &Path) -> String {
+    format!("#include \"{}\"\n", wrapper_path.display())
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|err| panic!("Couldn't read {}: {err}", path.display()))
+}
@@ -0,0 +1,67 @@
+//! The C compiler settings `build.rs` reads from `ir_cc.toml`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CcConfig {
+    pub flags: Vec<String>,
+    pub files: BTreeMap<String, FileConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    #[serde(default)]
+    pub flags: Vec<String>,
+    /// The only `CARGO_CFG_TARGET_ARCH` the file is compiled for.
+    pub target_arch: Option<String>,
+}
+
+impl CcConfig {
+    /// Loads the config and checks that every per-file entry names one of
+    /// `sources`, whose paths are relative to `c_src_path`.
+    pub fn load(path: &Path, c_src_path: &Path, sources: &[impl AsRef<Path>]) -> CcConfig {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let text = fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("Couldn't read {}: {err}", path.display()));
+        let config: CcConfig =
+            toml::from_str(&text).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+
+        for name in config.files.keys() {
+            let known = sources
+                .iter()
+                .any(|source| source.as_ref() == c_src_path.join(name));
+            if !known {
+                panic!(
+                    "{}: no C source `{name}` under {}",
+                    path.display(),
+                    c_src_path.display()
+                );
+            }
+        }
+        config
+    }
+
+    /// The extra flags for `source`, if it has any.
+    pub fn file_flags(&self, c_src_path: &Path, source: &Path) -> Option<&[String]> {
+        let file = self.file(c_src_path, source)?;
+        (!file.flags.is_empty()).then_some(file.flags.as_slice())
+    }
+
+    /// Whether `source` is compiled for `target_arch`.
+    pub fn builds_for(&self, c_src_path: &Path, source: &Path, target_arch: &str) -> bool {
+        self.file(c_src_path, source)
+            .and_then(|file| file.target_arch.as_deref())
+            .is_none_or(|arch| arch == target_arch)
+    }
+
+    fn file(&self, c_src_path: &Path, source: &Path) -> Option<&FileConfig> {
+        let name = source.strip_prefix(c_src_path).ok()?.to_str()?;
+        self.files.get(name)
+    }
+}
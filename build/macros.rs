@@ -0,0 +1,365 @@
+//! Reads the `#define`s of the headers under `c_src`, for what bindgen can't
+//! do with them.
+//!
+//! Bindgen binds an object-like macro as a `pub const` if its own expression
+//! parser can evaluate the value, and drops every function-like macro. A
+//! function-like macro annotated with `@ir inline(<prototype>)` is written
+//! out as a `static inline` function with that prototype, which expands the
+//! macro:
+//!
+//! ```c
+//! /**
+//!  * @ir inline(int clamp(int x, int lo, int hi))
+//!  */
+//! #define CLAMP(x, lo, hi) clamp_int((x), (lo), (hi))
+//! ```
+//!
+//! becomes `static inline int clamp(int x, int lo, int hi) { return CLAMP(x,
+//! lo, hi); }` in `$OUT_DIR/macro_fns/<module>.h`, which the umbrella header
+//! includes right after the header. The static function shim then binds it
+//! like any other, in the module of the header. The rest of the macro's doc
+//! comment goes on the function, so that other `@ir` annotations reach its
+//! safe wrapper.
+//!
+//! Any other macro that the bindings lose gets a build warning.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::{ForeignItem, Item};
+
+use crate::bindings::module_name;
+
+/// A `#define` in a header.
+#[derive(Debug)]
+pub struct Macro {
+    pub name: String,
+    /// The parameters of a function-like macro.
+    pub params: Option<Vec<String>>,
+    pub body: String,
+    /// The lines of the doc comment right before the `#define`, without the
+    /// comment markers.
+    pub doc: Vec<String>,
+}
+
+impl Macro {
+    /// The prototype in the `@ir inline` annotation, if there is one.
+    fn inline(&self) -> Option<Result<Prototype, String>> {
+        self.doc
+            .iter()
+            .find_map(|line| line.trim().strip_prefix("@ir inline"))
+            .map(|text| {
+                text.trim()
+                    .strip_prefix('(')
+                    .and_then(|text| text.strip_suffix(')'))
+                    .ok_or_else(|| format!("expected `inline(<prototype>)`, found `inline{text}`"))
+                    .and_then(Prototype::parse)
+            })
+    }
+}
+
+/// A C function prototype, e.g. `int clamp(int x, int lo, int hi)`.
+#[derive(Debug)]
+struct Prototype {
+    ret: String,
+    name: String,
+    /// The type and name of each parameter.
+    params: Vec<(String, String)>,
+}
+
+impl Prototype {
+    fn parse(text: &str) -> Result<Prototype, String> {
+        let (head, params) = text
+            .trim()
+            .strip_suffix(')')
+            .and_then(|text| text.split_once('('))
+            .ok_or_else(|| format!("expected a prototype, found `{text}`"))?;
+        let (ret, name) = split_declarator(head)
+            .ok_or_else(|| format!("`{}` needs a return type and a name", head.trim()))?;
+        let params = match params.trim() {
+            "" | "void" => Vec::new(),
+            params => params
+                .split(',')
+                .map(|param| {
+                    split_declarator(param).ok_or_else(|| {
+                        format!("parameter `{}` needs a type and a name", param.trim())
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Prototype { ret, name, params })
+    }
+
+    fn to_c(&self) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|(ty, name)| format!("{ty} {name}"))
+            .collect();
+        let params = if params.is_empty() {
+            "void".to_owned()
+        } else {
+            params.join(", ")
+        };
+        format!("{} {}({params})", self.ret, self.name)
+    }
+}
+
+/// Splits `const int* a` into `const int*` and `a`.
+fn split_declarator(text: &str) -> Option<(String, String)> {
+    let text = text.trim();
+    let start = text
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map_or(0, |i| i + 1);
+    let (ty, name) = (text[..start].trim(), &text[start..]);
+    let is_ident = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+    (!ty.is_empty() && is_ident).then(|| (ty.to_owned(), name.to_owned()))
+}
+
+/// The macros of a header, and the header of the functions generated from
+/// them.
+#[derive(Debug)]
+pub struct HeaderMacros {
+    pub macros: Vec<Macro>,
+    pub functions: Option<PathBuf>,
+}
+
+/// Reads the macros of every header, and writes the functions of their
+/// `@ir inline` annotations to `$OUT_DIR/macro_fns/<module>.h`.
+pub fn read(
+    c_src_path: &Path,
+    headers: &[PathBuf],
+    out_path: &Path,
+) -> BTreeMap<PathBuf, HeaderMacros> {
+    let dir = out_path.join("macro_fns");
+    let _ = fs::remove_dir_all(&dir);
+    let mut all = BTreeMap::new();
+    for header in headers {
+        let text = fs::read_to_string(header)
+            .unwrap_or_else(|err| panic!("Couldn't read {}: {err}", header.display()));
+        let macros = scan(&text);
+        let module = module_name(c_src_path, header);
+
+        let mut functions = String::new();
+        for m in &macros {
+            let Some(prototype) = m.inline() else {
+                continue;
+            };
+            let prototype = prototype
+                .unwrap_or_else(|err| panic!("`{module}::{}`: bad annotation: {err}", m.name));
+            functions.push_str(&inline_function(&module, m, &prototype));
+        }
+        let functions = (!functions.is_empty()).then(|| {
+            let guard = format!("MACRO_FNS_{}_H", module.to_uppercase());
+            let text = format!(
+                "// Generated by build.rs from the function-like macros in {}. Do not edit.\n\n\
+                 #ifndef {guard}\n#define {guard}\n\n\
+                 #include \"{}\"\n{functions}\n#endif\n",
+                header.display(),
+                header.canonicalize().unwrap().display()
+            );
+            fs::create_dir_all(&dir).expect("Couldn't create the macro function directory!");
+            let path = dir.join(format!("{module}.h"));
+            fs::write(&path, text).expect("Couldn't write the macro functions!");
+            path
+        });
+        all.insert(header.clone(), HeaderMacros { macros, functions });
+    }
+    all
+}
+
+/// The `static inline` function that expands `m`, with its doc comment.
+fn inline_function(module: &str, m: &Macro, prototype: &Prototype) -> String {
+    let Some(params) = &m.params else {
+        panic!(
+            "`{module}::{}`: `@ir inline` on an object-like macro",
+            m.name
+        );
+    };
+    if params.len() != prototype.params.len() {
+        panic!(
+            "`{module}::{}`: the macro takes {} arguments, the `@ir inline` prototype {}",
+            m.name,
+            params.len(),
+            prototype.params.len()
+        );
+    }
+    let mut doc: Vec<&str> = m
+        .doc
+        .iter()
+        .map(String::as_str)
+        .filter(|line| !line.trim().starts_with("@ir inline"))
+        .collect();
+    while doc.last().is_some_and(|line| line.trim().is_empty()) {
+        doc.pop();
+    }
+    let mut text = String::from("\n");
+    if !doc.is_empty() {
+        text.push_str("/**\n");
+        for line in doc {
+            text.push_str(format!(" * {line}").trim_end());
+            text.push('\n');
+        }
+        text.push_str(" */\n");
+    }
+    let args: Vec<&str> = prototype
+        .params
+        .iter()
+        .map(|(_, name)| name.as_str())
+        .collect();
+    let call = format!("{}({})", m.name, args.join(", "));
+    let body = if prototype.ret == "void" {
+        format!("{call};")
+    } else {
+        format!("return {call};")
+    };
+    text.push_str(&format!(
+        "static inline {} {{ {body} }}\n",
+        prototype.to_c()
+    ));
+    text
+}
+
+/// Finds the `#define`s in a header, with the doc comment before each.
+pub fn scan(text: &str) -> Vec<Macro> {
+    let mut macros = Vec::new();
+    let mut doc: Vec<String> = Vec::new();
+    let mut in_doc = false;
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if in_doc {
+            let end = trimmed.find("*/");
+            let content = end.map_or(trimmed, |end| &trimmed[..end]);
+            let content = content.strip_prefix('*').unwrap_or(content);
+            let content = content.strip_prefix(' ').unwrap_or(content);
+            if !(end.is_some() && content.trim().is_empty()) {
+                doc.push(content.trim_end().to_owned());
+            }
+            in_doc = end.is_none();
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix("/**") {
+            doc.clear();
+            match rest.find("*/") {
+                Some(end) => doc.push(rest[..end].trim().to_owned()),
+                None => {
+                    let rest = rest.trim();
+                    if !rest.is_empty() {
+                        doc.push(rest.to_owned());
+                    }
+                    in_doc = true;
+                }
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        let directive = trimmed.strip_prefix('#').map(str::trim_start);
+        let Some(definition) = directive.and_then(|d| d.strip_prefix("define")) else {
+            doc.clear();
+            continue;
+        };
+        // Join the lines a trailing backslash continues.
+        let mut definition = definition.to_owned();
+        while definition.ends_with('\\') {
+            definition.pop();
+            definition.push(' ');
+            definition.push_str(lines.next().unwrap_or_default().trim());
+        }
+        if let Some(m) = parse_define(&definition, std::mem::take(&mut doc)) {
+            macros.push(m);
+        }
+    }
+    macros
+}
+
+/// Parses what follows `#define`.
+fn parse_define(definition: &str, doc: Vec<String>) -> Option<Macro> {
+    let definition = definition.trim_start();
+    let end = definition
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(definition.len());
+    let name = &definition[..end];
+    if name.is_empty() {
+        return None;
+    }
+    let rest = &definition[end..];
+    // A parameter list has to follow the name without a space.
+    let (params, body) = match rest.strip_prefix('(') {
+        Some(rest) => {
+            let (params, body) = rest.split_once(')')?;
+            let params = params
+                .split(',')
+                .map(|param| param.trim().to_owned())
+                .filter(|param| !param.is_empty())
+                .collect();
+            (Some(params), body)
+        }
+        None => (None, rest),
+    };
+    let body = body
+        .split("//")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_owned();
+    Some(Macro {
+        name: name.to_owned(),
+        params,
+        body,
+        doc,
+    })
+}
+
+/// Prints a warning for each macro the bindings lose: function-like macros
+/// without `@ir inline`, and object-like macros bindgen couldn't evaluate.
+/// Macros without a body, such as include guards, have nothing to lose.
+pub fn report_lost(c_src_path: &Path, macros: &BTreeMap<PathBuf, HeaderMacros>, bindings: &str) {
+    let file = syn::parse_file(bindings).expect("Couldn't parse the bindings");
+    for (header, header_macros) in macros {
+        let module = module_name(c_src_path, header);
+        let items = file
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Mod(m) if m.ident == module => m.content.as_ref(),
+                _ => None,
+            })
+            .map_or(&[][..], |(_, items)| items.as_slice());
+        let bound = |name: &str| {
+            items.iter().any(|item| match item {
+                Item::Const(item) => item.ident == name,
+                Item::ForeignMod(foreign) => foreign
+                    .items
+                    .iter()
+                    .any(|item| matches!(item, ForeignItem::Fn(func) if func.sig.ident == name)),
+                _ => false,
+            })
+        };
+        for m in &header_macros.macros {
+            if m.body.is_empty() {
+                continue;
+            }
+            let lost = match (&m.params, m.inline()) {
+                (Some(_), Some(Ok(prototype))) if !bound(&prototype.name) => format!(
+                    "its `@ir inline` function `{}` is missing from the bindings",
+                    prototype.name
+                ),
+                (Some(_), Some(_)) => continue,
+                (Some(_), None) => {
+                    "bindgen drops function-like macros; an `@ir inline(<prototype>)` \
+                     annotation binds it as a function"
+                        .to_owned()
+                }
+                (None, _) if !bound(&m.name) => {
+                    format!("bindgen couldn't evaluate `{}`", m.body)
+                }
+                (None, _) => continue,
+            };
+            println!("cargo:warning=macro `{module}::{}` is lost: {lost}", m.name);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! The reproducibility manifest `build.rs` writes to `$OUT_DIR/manifest.json`.
+//!
+//! It records everything besides the sources that can change the generated
+//! IR: the compiler versions, every flag, and the bindgen options. Hashes of
+//! the sources and the generated bindings show whether the code changed. Two
+//! IR dumps with equal manifests come from equal inputs.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::toolchain::{LlvmVersion, Toolchain};
+
+pub struct Manifest<'a> {
+    /// The contents of `build_config.json`.
+    pub build_config: Value,
+    pub compiler: &'a cc::Tool,
+    /// The extra flags of the C sources with an entry in `ir_cc.toml`.
+    pub file_flags: BTreeMap<String, Vec<String>>,
+    /// The bindgen command line equivalent of each binding module.
+    pub bindgen_options: BTreeMap<String, Vec<String>>,
+    pub bindgen_version: Option<String>,
+    /// The libclang version bindgen ran with, if it ran.
+    pub libclang: Option<String>,
+    /// The headers, C sources and generated files to hash.
+    pub inputs: Vec<PathBuf>,
+}
+
+impl Manifest<'_> {
+    /// Writes `$OUT_DIR/manifest.json` and copies it to the IR output
+    /// directory: `$IR_OUTPUT_DIR` if set, otherwise `ir_output/` if it
+    /// exists. The copy is watched, so deleting it reruns the build.
+    pub fn write(&self, out_path: &Path) {
+        println!("cargo:rerun-if-env-changed=IR_OUTPUT_DIR");
+        let toolchain = Toolchain::from_env().unwrap_or_else(|err| panic!("{err}"));
+        let compiler_version = command_stdout(self.compiler.path(), "--version");
+
+        let encoded_rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+        let rustflags: Vec<&str> = encoded_rustflags
+            .split('\x1f')
+            .filter(|flag| !flag.is_empty())
+            .collect();
+        let hashes: BTreeMap<String, String> = self
+            .inputs
+            .iter()
+            .map(|path| (display_path(path, out_path), sha256(path)))
+            .collect();
+
+        let manifest = json!({
+            "rustc": {
+                "version": toolchain.rustc_version,
+                "llvm": toolchain.llvm.to_string(),
+            },
+            "c_compiler": {
+                "path": self.compiler.path(),
+                "version": compiler_version.lines().next(),
+                "llvm": LlvmVersion::from_version_output(&compiler_version)
+                    .map(|version| version.to_string()),
+            },
+            "libclang": self.libclang,
+            "build_config": self.build_config,
+            "c_flags": {
+                "common": self
+                    .compiler
+                    .args()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>(),
+                "per_file": self.file_flags,
+            },
+            "rustflags": rustflags,
+            "bindgen": {
+                "version": self.bindgen_version,
+                "options": self.bindgen_options,
+            },
+            "sha256": hashes,
+        });
+        let text = serde_json::to_string_pretty(&manifest).unwrap();
+        fs::write(out_path.join("manifest.json"), &text).expect("Couldn't write manifest.json!");
+
+        let ir_output = env::var_os("IR_OUTPUT_DIR")
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from("ir_output")).filter(|dir| dir.is_dir()));
+        if let Some(dir) = ir_output {
+            let copy = dir.join("manifest.json");
+            fs::create_dir_all(&dir)
+                .and_then(|()| fs::write(&copy, &text))
+                .and_then(|()| self.backdate(&copy, out_path))
+                .unwrap_or_else(|err| panic!("Couldn't copy manifest.json to {dir:?}: {err}"));
+            // Deleting the copy, or the whole directory, writes it again.
+            println!("cargo:rerun-if-changed={}", copy.display());
+        }
+    }
+
+    /// Gives `copy` the modification time of the newest input outside
+    /// `OUT_DIR`. Cargo reruns the build script for any watched file newer
+    /// than its last run, which a copy written during the run would be.
+    fn backdate(&self, copy: &Path, out_path: &Path) -> io::Result<()> {
+        let newest = self
+            .inputs
+            .iter()
+            .filter(|path| !path.starts_with(out_path))
+            .filter_map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+            .max();
+        match newest {
+            Some(time) => File::options().write(true).open(copy)?.set_modified(time),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Names files in `OUT_DIR` relative to it, since its path is different in
+/// every build.
+fn display_path(path: &Path, out_path: &Path) -> String {
+    match path.strip_prefix(out_path) {
+        Ok(relative) => format!("$OUT_DIR/{}", relative.display()),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+fn sha256(path: &Path) -> String {
+    let bytes = fs::read(path).unwrap_or_else(|err| panic!("Couldn't hash {path:?}: {err}"));
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn command_stdout(program: &Path, arg: &str) -> String {
+    Command::new(program)
+        .arg(arg)
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,977 @@
+//! Generates a safe `<fn>_safe` wrapper next to each binding, from `@ir`
+//! annotations in the doc comment of the C declaration.
+//!
+//! A function whose parameters are all values needs no annotation. Each
+//! pointer parameter needs one of:
+//!
+//! - `@ir slice(a, b, len)`: `a` and `b` point to `len` elements. The wrapper
+//!   takes a slice for each, checks that they have the same length and
+//!   passes it as `len`.
+//! - `@ir array(a, 4)`: `a` points to exactly 4 elements, taken as `&[T; 4]`.
+//! - `@ir ref(a)`: `a` points to a single value, taken as `&T`.
+//! - `@ir out(result, 4)`, or `@ir out(result)` for a single value: the
+//!   function writes every element of `result`. The wrapper returns what it
+//!   wrote, after the C return value if there is one.
+//!
+//! `*mut` pointers become `&mut` references. A function with an unannotated
+//! pointer parameter gets no wrapper, and the build prints a warning.
+//!
+//! A pointer to a struct that ends in a flexible array member takes none of
+//! these: a reference to the struct covers only its header, not the
+//! elements after it, so C reading them through it would be undefined
+//! behavior. Annotating one fails the build, and a function that takes one
+//! gets no wrapper, without a warning.
+//!
+//! `@ir flag(x)` and `@ir flag(return)` turn an `int` used as a flag into a
+//! `bool`.
+//!
+//! `@ir checked(result)`: the function returns whether the operation
+//! overflowed, as the `__builtin_*_overflow` functions do, and otherwise
+//! writes the result through `result`. The wrapper returns `Option`, `None`
+//! on overflow, like Rust's `checked_*` methods.
+//!
+//! `@ir target_feature(avx2)`: the function is compiled for a CPU feature
+//! that not every CPU of the target has. The wrapper checks that this one
+//! does, and panics otherwise.
+//!
+//! `@ir callback(f, context)` lets the wrapper take a Rust closure for the
+//! function pointer `f`. The last parameter of `f` must be a `void*` that the
+//! C function passes through from `context`; the wrapper passes the closure
+//! there and `f` points to a trampoline that calls it. Pointer arguments of
+//! `f` reach the closure as references. A panic in the closure aborts, as
+//! it can't unwind through C. So functions with callbacks get another
+//! wrapper, `<fn>_catch_unwind`, that returns the panic as `Err` instead; see
+//! [`OnPanic`].
+//!
+//! `@ir unwind(f)`: a panic in callback `f` may unwind through the C
+//! function, which adds a `<fn>_c_unwind` wrapper over the `C-unwind` ABI.
+//! The C file that defines the function must be compiled with unwind tables,
+//! i.e. with `-fexceptions` in `ir_cc.toml`; unwinding through C frames
+//! without them is undefined behavior.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use bindgen::callbacks::ParseCallbacks;
+use proc_macro2::{Literal, TokenStream};
+use quote::{ToTokens, format_ident, quote};
+use syn::{FnArg, ForeignItem, ForeignItemFn, Item, Pat, ReturnType, Type, TypeBareFn};
+
+/// An `@ir` annotation.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Annotation {
+    Slice {
+        pointers: Vec<String>,
+        len: String,
+    },
+    Array {
+        pointer: String,
+        len: usize,
+    },
+    Ref {
+        pointer: String,
+    },
+    Out {
+        pointer: String,
+        len: Option<usize>,
+    },
+    /// `param` is `return` for the return value.
+    Flag {
+        param: String,
+    },
+    Callback {
+        callback: String,
+        context: String,
+    },
+    Checked {
+        pointer: String,
+    },
+    /// `callback` may unwind through the function.
+    Unwind {
+        callback: String,
+    },
+    /// A CPU feature, e.g. `avx2`, that the function's code needs.
+    TargetFeature {
+        feature: String,
+    },
+}
+
+impl Annotation {
+    /// Parses the text after `@ir`, e.g. `slice(a, b, len)`.
+    pub fn parse(text: &str) -> Result<Annotation, String> {
+        let text = text.trim();
+        let (kind, args) = text
+            .strip_suffix(')')
+            .and_then(|text| text.split_once('('))
+            .ok_or_else(|| format!("expected `kind(arguments)`, found `{text}`"))?;
+        let kind = kind.trim();
+        let args: Vec<String> = args.split(',').map(|arg| arg.trim().to_owned()).collect();
+        // Feature names such as `sse4.1` have a dot.
+        if let Some(arg) = args.iter().find(|arg| {
+            arg.is_empty()
+                || !arg
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        }) {
+            return Err(format!(
+                "`{arg}` is not a parameter name, a length or a feature"
+            ));
+        }
+
+        let parse_len = |len: &str| {
+            len.parse::<usize>()
+                .map_err(|_| format!("`{kind}` needs a constant length, found `{len}`"))
+        };
+        match (kind, args.as_slice()) {
+            ("slice", [pointers @ .., len]) if !pointers.is_empty() => Ok(Annotation::Slice {
+                pointers: pointers.to_vec(),
+                len: len.clone(),
+            }),
+            ("array", [pointer, len]) => Ok(Annotation::Array {
+                pointer: pointer.clone(),
+                len: parse_len(len)?,
+            }),
+            ("ref", [pointer]) => Ok(Annotation::Ref {
+                pointer: pointer.clone(),
+            }),
+            ("out", [pointer]) => Ok(Annotation::Out {
+                pointer: pointer.clone(),
+                len: None,
+            }),
+            ("out", [pointer, len]) => Ok(Annotation::Out {
+                pointer: pointer.clone(),
+                len: Some(parse_len(len)?),
+            }),
+            ("flag", [param]) => Ok(Annotation::Flag {
+                param: param.clone(),
+            }),
+            ("callback", [callback, context]) => Ok(Annotation::Callback {
+                callback: callback.clone(),
+                context: context.clone(),
+            }),
+            ("checked", [pointer]) => Ok(Annotation::Checked {
+                pointer: pointer.clone(),
+            }),
+            ("unwind", [callback]) => Ok(Annotation::Unwind {
+                callback: callback.clone(),
+            }),
+            ("target_feature", [feature]) => Ok(Annotation::TargetFeature {
+                feature: feature.clone(),
+            }),
+            (
+                "slice" | "array" | "ref" | "out" | "flag" | "callback" | "checked" | "unwind"
+                | "target_feature",
+                _,
+            ) => Err(format!("wrong number of arguments to `{kind}`")),
+            _ => Err(format!(
+                "unknown annotation `{kind}`, expected `slice`, `array`, `ref`, `out`, `flag`, \
+                 `callback`, `checked`, `unwind` or `target_feature`"
+            )),
+        }
+    }
+
+    /// Parses every `@ir` line of a doc comment.
+    fn from_doc(doc: &str) -> Result<Vec<Annotation>, String> {
+        doc.lines()
+            .filter_map(|line| line.trim().strip_prefix("@ir"))
+            .map(Annotation::parse)
+            .collect()
+    }
+
+    /// The parameters this annotation names as pointers.
+    fn pointers(&self) -> &[String] {
+        match self {
+            Annotation::Slice { pointers, .. } => pointers,
+            Annotation::Array { pointer, .. }
+            | Annotation::Ref { pointer }
+            | Annotation::Out { pointer, .. }
+            | Annotation::Checked { pointer } => std::slice::from_ref(pointer),
+            Annotation::Flag { .. }
+            | Annotation::Callback { .. }
+            | Annotation::Unwind { .. }
+            | Annotation::TargetFeature { .. } => &[],
+        }
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Annotation::Slice { pointers, len } => {
+                write!(f, "@ir slice({}, {len})", pointers.join(", "))
+            }
+            Annotation::Array { pointer, len } => write!(f, "@ir array({pointer}, {len})"),
+            Annotation::Ref { pointer } => write!(f, "@ir ref({pointer})"),
+            Annotation::Out { pointer, len: None } => write!(f, "@ir out({pointer})"),
+            Annotation::Out {
+                pointer,
+                len: Some(len),
+            } => write!(f, "@ir out({pointer}, {len})"),
+            Annotation::Flag { param } => write!(f, "@ir flag({param})"),
+            Annotation::Callback { callback, context } => {
+                write!(f, "@ir callback({callback}, {context})")
+            }
+            Annotation::Checked { pointer } => write!(f, "@ir checked({pointer})"),
+            Annotation::Unwind { callback } => write!(f, "@ir unwind({callback})"),
+            Annotation::TargetFeature { feature } => write!(f, "@ir target_feature({feature})"),
+        }
+    }
+}
+
+/// Checks the `@ir` annotations while bindgen reads the headers, so that a
+/// typo fails the build at the comment rather than silently dropping a
+/// wrapper, and writes them in one spelling into the binding docs.
+#[derive(Debug)]
+pub struct AnnotationCallbacks;
+
+impl ParseCallbacks for AnnotationCallbacks {
+    fn process_comment(&self, comment: &str) -> Option<String> {
+        if !comment.contains("@ir") {
+            return None;
+        }
+        let lines: Vec<String> = comment
+            .lines()
+            .map(|line| {
+                let Some(text) = line.trim().strip_prefix("@ir") else {
+                    return line.to_owned();
+                };
+                let indent = &line[..line.len() - line.trim_start().len()];
+                match Annotation::parse(text) {
+                    Ok(annotation) => format!("{indent}{annotation}"),
+                    Err(err) => panic!("bad annotation `{}`: {err}", line.trim()),
+                }
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+/// Writes `$OUT_DIR/safe_wrappers/<module>.rs` for every binding module in
+/// `bindings`. Each binding module includes its file.
+pub fn generate(bindings: &str, out_path: &Path) {
+    let dir = out_path.join("safe_wrappers");
+    fs::create_dir_all(&dir).expect("Couldn't create the safe wrapper directory!");
+    let file = syn::parse_file(bindings).expect("Couldn't parse the bindings");
+    for item in file.items {
+        let Item::Mod(module) = item else { continue };
+        let items = module.content.map(|(_, items)| items).unwrap_or_default();
+        let flexible = flexible_structs(&items);
+        let mut wrappers = TokenStream::new();
+        for func in items
+            .iter()
+            .filter_map(|item| match item {
+                Item::ForeignMod(foreign) => Some(&foreign.items),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|item| match item {
+                ForeignItem::Fn(func) => Some(func),
+                _ => None,
+            })
+        {
+            match wrapper(func, &flexible) {
+                Ok(tokens) => wrappers.extend(tokens),
+                Err(reason) => println!(
+                    "cargo:warning=no safe wrapper for `{}::{}`: {reason}",
+                    module.ident, func.sig.ident
+                ),
+            }
+        }
+
+        let file: syn::File = syn::parse2(wrappers).expect("Couldn't parse a safe wrapper");
+        fs::write(
+            dir.join(format!("{}.rs", module.ident)),
+            prettyplease::unparse(&file),
+        )
+        .expect("Couldn't write the safe wrappers!");
+    }
+}
+
+/// What a C parameter becomes in the wrapper.
+enum Param<'a> {
+    Value,
+    Slice,
+    SliceLen { pointer: &'a str },
+    Array(usize),
+    Ref,
+    Out(Option<usize>),
+    Flag,
+    Callback(&'a TypeBareFn),
+    Context { callback: &'a str },
+}
+
+/// Builds the wrapper of `func`, or says why it has none. Annotations that
+/// don't fit the signature fail the build. `flexible` names the structs of
+/// the module that end in a flexible array member.
+fn wrapper(func: &ForeignItemFn, flexible: &BTreeSet<String>) -> Result<TokenStream, String> {
+    let name = &func.sig.ident;
+    let doc = doc_comment(func);
+    let annotations =
+        Annotation::from_doc(&doc).unwrap_or_else(|err| panic!("`{name}`: bad annotation: {err}"));
+    if func.sig.variadic.is_some() {
+        return Err("it is variadic".to_owned());
+    }
+
+    let params: Vec<(&syn::Ident, &Type)> = func
+        .sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) => (&pat.ident, &*arg.ty),
+                _ => panic!("`{name}`: unnamed parameter"),
+            },
+            FnArg::Receiver(_) => unreachable!("extern functions have no receiver"),
+        })
+        .collect();
+    let find = |param: &str| {
+        params
+            .iter()
+            .position(|(ident, _)| *ident == param)
+            .unwrap_or_else(|| panic!("`{name}`: annotation names unknown parameter `{param}`"))
+    };
+
+    let mut flag_return = false;
+    let mut checked = false;
+    let mut roles: Vec<Option<Param>> = params.iter().map(|_| None).collect();
+    let mut assign = |index: usize, role| {
+        if roles[index].is_some() {
+            panic!("`{name}`: `{}` is annotated twice", params[index].0);
+        }
+        roles[index] = Some(role);
+    };
+    for annotation in &annotations {
+        for pointer in annotation.pointers() {
+            let index = find(pointer);
+            let Type::Ptr(ptr) = params[index].1 else {
+                panic!("`{name}`: `{pointer}` is annotated but not a pointer");
+            };
+            if let Some(pointee) = flexible_pointee(ptr, flexible) {
+                panic!(
+                    "`{name}`: `{pointer}` is annotated, but `{pointee}` ends in a flexible \
+                     array member that a reference can't reach"
+                );
+            }
+            let role = match annotation {
+                Annotation::Slice { .. } => Param::Slice,
+                Annotation::Array { len, .. } => Param::Array(*len),
+                Annotation::Ref { .. } => Param::Ref,
+                Annotation::Out { len, .. } => {
+                    if ptr.mutability.is_none() {
+                        panic!("`{name}`: output `{pointer}` is a const pointer");
+                    }
+                    Param::Out(*len)
+                }
+                Annotation::Checked { .. } => {
+                    if ptr.mutability.is_none() {
+                        panic!("`{name}`: checked result `{pointer}` is a const pointer");
+                    }
+                    if !matches!(&func.sig.output, ReturnType::Type(_, ty)
+                        if matches!(&**ty, Type::Path(path) if path.path.is_ident("bool")))
+                    {
+                        panic!("`{name}`: checked, but it doesn't return an overflow `bool`");
+                    }
+                    checked = true;
+                    Param::Out(None)
+                }
+                Annotation::Flag { .. }
+                | Annotation::Callback { .. }
+                | Annotation::Unwind { .. }
+                | Annotation::TargetFeature { .. } => {
+                    unreachable!("flags, callbacks and features name no pointers")
+                }
+            };
+            assign(index, role);
+        }
+        if let Annotation::Slice { pointers, len } = annotation {
+            let index = find(len);
+            if matches!(params[index].1, Type::Ptr(_)) {
+                panic!("`{name}`: slice length `{len}` is a pointer");
+            }
+            assign(
+                index,
+                Param::SliceLen {
+                    pointer: &pointers[0],
+                },
+            );
+        }
+        if let Annotation::Flag { param } = annotation {
+            if param == "return" {
+                if matches!(func.sig.output, ReturnType::Default) {
+                    panic!("`{name}`: flag return value, but it returns nothing");
+                }
+                flag_return = true;
+            } else {
+                let index = find(param);
+                if matches!(params[index].1, Type::Ptr(_)) {
+                    panic!("`{name}`: flag `{param}` is a pointer");
+                }
+                assign(index, Param::Flag);
+            }
+        }
+        if let Annotation::Callback { callback, context } = annotation {
+            let index = find(callback);
+            let bare = function_pointer(params[index].1).unwrap_or_else(|| {
+                panic!("`{name}`: callback `{callback}` is not a function pointer")
+            });
+            if !bare
+                .inputs
+                .last()
+                .is_some_and(|arg| is_void_pointer(&arg.ty))
+            {
+                panic!("`{name}`: the last parameter of `{callback}` must be a `void*` context");
+            }
+            assign(index, Param::Callback(bare));
+            let index = find(context);
+            if !is_void_pointer(params[index].1) {
+                panic!("`{name}`: context `{context}` is not a `void*`");
+            }
+            assign(index, Param::Context { callback });
+        }
+    }
+    for (role, (ident, ty)) in roles.iter_mut().zip(&params) {
+        if role.is_none() {
+            if let Type::Ptr(ptr) = ty
+                && flexible_pointee(ptr, flexible).is_some()
+            {
+                return Ok(TokenStream::new());
+            }
+            if matches!(ty, Type::Ptr(_)) {
+                return Err(format!("pointer parameter `{ident}` has no @ir annotation"));
+            }
+            if function_pointer(ty).is_some() {
+                return Err(format!("function pointer `{ident}` has no @ir annotation"));
+            }
+            *role = Some(Param::Value);
+        }
+    }
+
+    let roles: Vec<Param> = roles.into_iter().map(Option::unwrap).collect();
+    let outs = roles
+        .iter()
+        .filter(|role| matches!(role, Param::Out(_)))
+        .count();
+    let has_callback = roles.iter().any(|role| matches!(role, Param::Callback(_)));
+    if checked && (outs > 1 || flag_return || has_callback) {
+        panic!(
+            "`{name}`: a checked result can't be combined with other outputs, flags or callbacks"
+        );
+    }
+    let mut unwind = false;
+    for annotation in &annotations {
+        if let Annotation::Unwind { callback } = annotation {
+            let index = find(callback);
+            if !matches!(roles[index], Param::Callback(_)) {
+                panic!("`{name}`: `{callback}` may unwind, but it isn't an @ir callback");
+            }
+            unwind = true;
+        }
+    }
+    let modes: &[OnPanic] = match (has_callback, unwind) {
+        (true, true) => &[OnPanic::Abort, OnPanic::Catch, OnPanic::Unwind],
+        (true, false) => &[OnPanic::Abort, OnPanic::Catch],
+        (false, _) => &[OnPanic::Abort],
+    };
+    let mut wrappers = TokenStream::new();
+    for &mode in modes {
+        wrappers.extend(variant(
+            func,
+            &doc,
+            &annotations,
+            &params,
+            &roles,
+            flag_return,
+            mode,
+        ));
+    }
+    Ok(wrappers)
+}
+
+/// What happens when a closure passed as a callback panics.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OnPanic {
+    /// `<fn>_safe`: the process aborts, as the panic can't unwind through C.
+    Abort,
+    /// `<fn>_catch_unwind`: the trampoline catches the panic, and the wrapper
+    /// returns it once the C function returns.
+    Catch,
+    /// `<fn>_c_unwind`, with `@ir unwind`: the panic unwinds through the C
+    /// frames, over the `C-unwind` ABI, and the wrapper catches it.
+    Unwind,
+}
+
+/// Builds one wrapper of `func`, for the given panic handling.
+fn variant(
+    func: &ForeignItemFn,
+    doc: &str,
+    annotations: &[Annotation],
+    params: &[(&syn::Ident, &Type)],
+    roles: &[Param],
+    flag_return: bool,
+    mode: OnPanic,
+) -> TokenStream {
+    let name = &func.sig.ident;
+    let checked = annotations
+        .iter()
+        .any(|annotation| matches!(annotation, Annotation::Checked { .. }));
+    let abi = match mode {
+        OnPanic::Unwind => quote!(extern "C-unwind"),
+        OnPanic::Abort | OnPanic::Catch => quote!(extern "C"),
+    };
+    let mut inputs = Vec::new();
+    let mut checks = Vec::new();
+    let mut args = Vec::new();
+    let mut outs = Vec::new();
+    let mut out_types = Vec::new();
+    // The callbacks whose panics the `Catch` variant keeps next to them.
+    let mut callbacks = Vec::new();
+    let mut declared = Vec::new();
+    for (role, (ident, ty)) in roles.iter().zip(params) {
+        declared.push(quote!(#ident: #ty));
+        let (elem, mutable) = match ty {
+            Type::Ptr(ptr) => (&*ptr.elem, ptr.mutability.is_some()),
+            _ => (*ty, false),
+        };
+        let reference = if mutable { quote!(&mut) } else { quote!(&) };
+        let as_ptr = if mutable {
+            quote!(as_mut_ptr)
+        } else {
+            quote!(as_ptr)
+        };
+        match role {
+            Param::Value => {
+                inputs.push(quote!(#ident: #ty));
+                args.push(quote!(#ident));
+            }
+            Param::Slice => {
+                inputs.push(quote!(#ident: #reference [#elem]));
+                args.push(quote!(#ident.#as_ptr()));
+            }
+            Param::SliceLen { pointer } => {
+                let pointer = format_ident!("{pointer}");
+                if is_usize(ty) {
+                    args.push(quote!(#pointer.len()));
+                } else {
+                    args.push(quote!(#pointer.len().try_into().expect("slice is too long")));
+                }
+            }
+            Param::Array(len) => {
+                let len = Literal::usize_unsuffixed(*len);
+                inputs.push(quote!(#ident: #reference [#elem; #len]));
+                args.push(quote!(#ident.#as_ptr()));
+            }
+            Param::Ref => {
+                inputs.push(quote!(#ident: #reference #elem));
+                args.push(quote!(#ident));
+            }
+            Param::Out(len) => {
+                let out_ty = match len.map(Literal::usize_unsuffixed) {
+                    Some(len) => quote!([#elem; #len]),
+                    None => quote!(#elem),
+                };
+                // Not every C type is valid zeroed, e.g. a Rust enum without
+                // a zero variant, so the value is only read once C wrote it.
+                checks.push(quote! {
+                    let mut #ident = ::std::mem::MaybeUninit::<#out_ty>::uninit();
+                });
+                args.push(quote!(#ident.as_mut_ptr().cast()));
+                outs.push(quote!(unsafe { #ident.assume_init() }));
+                out_types.push(out_ty);
+            }
+            Param::Flag => {
+                inputs.push(quote!(#ident: bool));
+                args.push(quote!(#ty::from(#ident)));
+            }
+            Param::Callback(bare) => {
+                let bare_args: Vec<&syn::BareFnArg> = bare.inputs.iter().collect();
+                let (bare_params, context) = bare_args.split_at(bare_args.len() - 1);
+                let context = arg_name(context[0], bare_params.len());
+                let mut closure_args = Vec::new();
+                let mut tramp_params = Vec::new();
+                let mut tramp_args = Vec::new();
+                let mut derefs = Vec::new();
+                for (i, arg) in bare_params.iter().enumerate() {
+                    let (arg_name, arg_ty) = (arg_name(arg, i), &arg.ty);
+                    tramp_params.push(quote!(#arg_name: #arg_ty));
+                    tramp_args.push(quote!(#arg_name));
+                    match arg_ty {
+                        Type::Ptr(ptr) => {
+                            let elem = &ptr.elem;
+                            let reference = match ptr.mutability {
+                                Some(_) => quote!(&mut),
+                                None => quote!(&),
+                            };
+                            closure_args.push(quote!(#reference #elem));
+                            derefs.push(quote!(let #arg_name = unsafe { #reference *#arg_name };));
+                        }
+                        _ => closure_args.push(quote!(#arg_ty)),
+                    }
+                }
+                let output = &bare.output;
+                let closure = quote!(FnMut(#(#closure_args),*) #output);
+                let trampoline = format_ident!("{ident}_trampoline");
+                let trampoline_of = format_ident!("{ident}_trampoline_of");
+                let fn_ty = TypeBareFn {
+                    abi: Some(syn::parse2(abi.clone()).unwrap()),
+                    inputs: bare
+                        .inputs
+                        .iter()
+                        .map(|arg| syn::BareFnArg {
+                            name: None,
+                            ..arg.clone()
+                        })
+                        .collect(),
+                    ..(*bare).clone()
+                };
+                let declared_ty = TypeBareFn {
+                    abi: fn_ty.abi.clone(),
+                    ..(*bare).clone()
+                };
+                *declared.last_mut().unwrap() = quote!(#ident: ::std::option::Option<#declared_ty>);
+
+                // The context is the closure, borrowed for the whole call, and
+                // the other pointers are valid during the callback.
+                let tramp_body = match mode {
+                    OnPanic::Abort | OnPanic::Unwind => quote! {
+                        let closure = unsafe { &mut *#context.cast::<F>() };
+                        #(#derefs)*
+                        closure(#(#tramp_args),*)
+                    },
+                    OnPanic::Catch => {
+                        // C still needs a return value after a panic. Zero is
+                        // a valid number or pointer, but not, e.g., a Rust
+                        // enum without a zero variant.
+                        let fallback = match output {
+                            ReturnType::Default => quote!(),
+                            ReturnType::Type(_, ty) if zero_is_valid(ty) => {
+                                quote!(unsafe { ::std::mem::zeroed() })
+                            }
+                            ReturnType::Type(_, ty) => panic!(
+                                "`{name}`: callback `{ident}` returns `{}`, which may not be \
+                                 valid zeroed, so `{name}_catch_unwind` has nothing to return \
+                                 to C after a panic",
+                                ty.to_token_stream()
+                            ),
+                        };
+                        quote! {
+                            let (closure, panic) = unsafe {
+                                &mut *#context
+                                    .cast::<(F, Option<Box<dyn ::std::any::Any + Send>>)>()
+                            };
+                            if panic.is_none() {
+                                #(#derefs)*
+                                let call = ::std::panic::AssertUnwindSafe(|| {
+                                    closure(#(#tramp_args),*)
+                                });
+                                match ::std::panic::catch_unwind(call) {
+                                    Ok(ret) => return ret,
+                                    Err(payload) => *panic = Some(payload),
+                                }
+                            }
+                            #fallback
+                        }
+                    }
+                };
+                checks.push(quote! {
+                    unsafe #abi fn #trampoline<F: #closure>(
+                        #(#tramp_params,)*
+                        #context: *mut ::std::os::raw::c_void,
+                    ) #output {
+                        #tramp_body
+                    }
+                    fn #trampoline_of<F: #closure>(_: &F) -> #fn_ty {
+                        #trampoline::<F>
+                    }
+                });
+                if mode == OnPanic::Catch {
+                    inputs.push(quote!(#ident: impl #closure));
+                    checks.push(quote! {
+                        let mut #ident = (#ident, None::<Box<dyn ::std::any::Any + Send>>);
+                    });
+                    args.push(quote!(Some(#trampoline_of(&#ident.0))));
+                    callbacks.push(*ident);
+                } else {
+                    inputs.push(quote!(mut #ident: impl #closure));
+                    args.push(quote!(Some(#trampoline_of(&#ident))));
+                }
+            }
+            Param::Context { callback } => {
+                let callback = format_ident!("{callback}");
+                args.push(quote!(::std::ptr::from_mut(&mut #callback).cast()));
+            }
+        }
+    }
+    for annotation in annotations {
+        if let Annotation::TargetFeature { feature } = annotation {
+            // Only x86 can detect the features; elsewhere the check fails.
+            let message = format!("`{name}` needs a CPU with `{feature}`");
+            checks.insert(
+                0,
+                quote! {
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    let detected = ::std::arch::is_x86_feature_detected!(#feature);
+                    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+                    let detected = false;
+                    assert!(detected, #message);
+                },
+            );
+        }
+        if let Annotation::Slice { pointers, .. } = annotation {
+            let first = format_ident!("{}", pointers[0]);
+            for other in &pointers[1..] {
+                let message = format!("`{}` and `{other}` must have the same length", pointers[0]);
+                let other = format_ident!("{other}");
+                checks.push(quote!(assert_eq!(#first.len(), #other.len(), #message);));
+            }
+        }
+    }
+
+    let (ret, call) = match &func.sig.output {
+        ReturnType::Type(..) if flag_return => (
+            Some(quote!(bool)),
+            quote!(unsafe { #name(#(#args),*) != 0 }),
+        ),
+        ReturnType::Type(_, ty) => (Some(quote!(#ty)), quote!(unsafe { #name(#(#args),*) })),
+        ReturnType::Default => (None, quote!(unsafe { #name(#(#args),*) })),
+    };
+    let (output, body) = match mode {
+        OnPanic::Abort if checked => (
+            quote!(-> Option<#(#out_types)*>),
+            // C only writes the result when it doesn't overflow.
+            quote!(let overflowed = #call; (!overflowed).then(|| #(#outs)*)),
+        ),
+        OnPanic::Abort => match (ret, outs.as_slice()) {
+            (None, []) => (quote!(), call),
+            (Some(ret), []) => (quote!(-> #ret), call),
+            (None, [out]) => (quote!(-> #(#out_types)*), quote!(#call; #out)),
+            (None, _) => (quote!(-> (#(#out_types),*)), quote!(#call; (#(#outs),*))),
+            (Some(ret), _) => (
+                quote!(-> (#ret, #(#out_types),*)),
+                quote!(let ret = #call; (ret, #(#outs),*)),
+            ),
+        },
+        OnPanic::Catch | OnPanic::Unwind => {
+            let (ok_ty, value) = match (&ret, outs.as_slice()) {
+                (None, []) => (quote!(()), quote!(())),
+                (Some(ret), []) => (quote!(#ret), quote!(ret)),
+                (None, [out]) => (quote!(#(#out_types)*), quote!(#out)),
+                (None, _) => (quote!((#(#out_types),*)), quote!((#(#outs),*))),
+                (Some(ret), _) => (quote!((#ret, #(#out_types),*)), quote!((ret, #(#outs),*))),
+            };
+            let call = match mode {
+                OnPanic::Unwind => quote! {
+                    ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| #call))?
+                },
+                _ => call,
+            };
+            let call = match ret {
+                Some(_) => quote!(let ret = #call;),
+                None => quote!(#call;),
+            };
+            (
+                quote!(-> Result<#ok_ty, Box<dyn ::std::any::Any + Send>>),
+                quote! {
+                    #call
+                    #(
+                        if let Some(payload) = #callbacks.1 {
+                            return Err(payload);
+                        }
+                    )*
+                    Ok(#value)
+                },
+            )
+        }
+    };
+    // Bindgen declares the function `extern "C"`, which must not unwind, so
+    // the `C-unwind` variant declares it again.
+    let body = match mode {
+        OnPanic::Unwind => {
+            let attrs = func
+                .attrs
+                .iter()
+                .filter(|attr| !attr.path().is_ident("doc"));
+            let ret = &func.sig.output;
+            quote! {
+                #[allow(clashing_extern_declarations)]
+                unsafe extern "C-unwind" {
+                    #(#attrs)*
+                    fn #name(#(#declared),*) #ret;
+                }
+                #body
+            }
+        }
+        _ => body,
+    };
+
+    let safe = format_ident!("{name}_safe");
+    let (wrapper, summary) = match mode {
+        OnPanic::Abort => (safe, format!(" Safe wrapper around [`{name}`].")),
+        OnPanic::Catch => (
+            format_ident!("{name}_catch_unwind"),
+            format!(
+                " Like [`{safe}`], but a panic in a callback is caught before it reaches C,\n \
+                 and returned as `Err` once `{name}` returns. A callback isn't called\n \
+                 again after it panics; C gets zero, or a null pointer, from it instead."
+            ),
+        ),
+        OnPanic::Unwind => (
+            format_ident!("{name}_c_unwind"),
+            format!(
+                " Like [`{safe}`], but calls `{name}` and the callbacks over the\n \
+                 `C-unwind` ABI, so that a panic in a callback unwinds through the C\n \
+                 frames and is returned as `Err`. The C code needs unwind tables."
+            ),
+        ),
+    };
+    let mut docs: Vec<String> = doc
+        .lines()
+        .filter(|line| !line.trim().starts_with("@ir"))
+        .map(str::to_owned)
+        .collect();
+    while docs.last().is_some_and(|line| line.trim().is_empty()) {
+        docs.pop();
+    }
+    if !docs.is_empty() {
+        docs.push(String::new());
+    }
+    docs.extend(summary.lines().map(str::to_owned));
+    quote! {
+        #(#[doc = #docs])*
+        pub fn #wrapper(#(#inputs),*) #output {
+            #(#checks)*
+            #body
+        }
+    }
+}
+
+/// The doc comment of `func`, one line per line of the C comment.
+fn doc_comment(func: &ForeignItemFn) -> String {
+    let mut doc = String::new();
+    for attr in &func.attrs {
+        if let syn::Meta::NameValue(meta) = &attr.meta
+            && meta.path.is_ident("doc")
+            && let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(text),
+                ..
+            }) = &meta.value
+        {
+            doc.push_str(&text.value());
+            doc.push('\n');
+        }
+    }
+    doc
+}
+
+/// The structs among `items` whose last field is a flexible array member,
+/// which bindgen types as `__IncompleteArrayField<T>`.
+fn flexible_structs(items: &[Item]) -> BTreeSet<String> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(item) => Some(item),
+            _ => None,
+        })
+        .filter(|item| {
+            item.fields.iter().last().is_some_and(|field| {
+                matches!(&field.ty, Type::Path(path)
+                    if path.path.segments.last()
+                        .is_some_and(|s| s.ident == "__IncompleteArrayField"))
+            })
+        })
+        .map(|item| item.ident.to_string())
+        .collect()
+}
+
+/// The struct `ptr` points to, if it is one of `flexible`.
+fn flexible_pointee(ptr: &syn::TypePtr, flexible: &BTreeSet<String>) -> Option<String> {
+    let Type::Path(path) = &*ptr.elem else {
+        return None;
+    };
+    let pointee = path.path.segments.last()?.ident.to_string();
+    flexible.contains(&pointee).then_some(pointee)
+}
+
+/// The function type of a bindgen function pointer, `Option<unsafe extern
+/// "C" fn(..)>`.
+fn function_pointer(ty: &Type) -> Option<&TypeBareFn> {
+    let Type::Path(path) = ty else { return None };
+    let last = path.path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(Type::BareFn(bare)) => Some(bare),
+        _ => None,
+    }
+}
+
+/// The name of a function pointer parameter, or `argN` if it has none.
+fn arg_name(arg: &syn::BareFnArg, index: usize) -> syn::Ident {
+    match &arg.name {
+        Some((name, _)) => name.clone(),
+        None => format_ident!("arg{}", index + 1),
+    }
+}
+
+/// Whether all-zero bytes are a valid `ty`: a number, `bool`, pointer or
+/// function pointer.
+fn zero_is_valid(ty: &Type) -> bool {
+    match ty {
+        Type::Ptr(_) => true,
+        Type::Path(path) => {
+            function_pointer(ty).is_some()
+                || path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| ZERO_VALID.contains(&segment.ident.to_string().as_str()))
+        }
+        _ => false,
+    }
+}
+
+const ZERO_VALID: [&str; 28] = [
+    "bool",
+    "f32",
+    "f64",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "c_char",
+    "c_schar",
+    "c_uchar",
+    "c_short",
+    "c_ushort",
+    "c_int",
+    "c_uint",
+    "c_long",
+    "c_ulong",
+    "c_longlong",
+    "c_ulonglong",
+    "c_float",
+    "c_double",
+];
+
+fn is_void_pointer(ty: &Type) -> bool {
+    matches!(ty, Type::Ptr(ptr) if ptr.mutability.is_some()
+        && matches!(&*ptr.elem, Type::Path(path)
+            if path.path.segments.last().is_some_and(|s| s.ident == "c_void")))
+}
+
+fn is_usize(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.is_ident("usize"))
+}
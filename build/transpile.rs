@@ -0,0 +1,577 @@
+//! Translates simple `static inline` C functions into `#[inline]` Rust
+//! functions, a third point of comparison next to the FFI call through the
+//! shim and the hand-written port.
+//!
+//! Bodies may use `return`, `if`/`else` and initialized locals, over integer,
+//! floating-point and `_Bool` values: literals, parameters, casts, the
+//! arithmetic, bitwise, comparison and logical operators, and `?:`. The
+//! translation follows C: operands are converted the way clang converted
+//! them, comparisons and `!`, `&&`, `||` yield `int`, and integer arithmetic
+//! wraps. For signed overflow, which C leaves undefined (clang marks that
+//! arithmetic `nsw` in the shims), wrapping is the transpiler's choice.
+//! Anything else, such as pointers, calls, loops or assignments, is skipped
+//! with a build warning that names the function and what stopped it.
+
+// The libclang constants keep their C names.
+#![allow(non_upper_case_globals)]
+
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use clang_sys::*;
+
+use crate::bindings::module_name;
+
+/// Writes `$OUT_DIR/transpiled.rs`, with one module per header under
+/// `c_src`, and returns its path. libclang must already be loaded.
+pub fn transpile(
+    c_src_path: &Path,
+    headers: &[PathBuf],
+    wrapper_path: &Path,
+    out_path: &Path,
+) -> PathBuf {
+    let tu = TranslationUnit::parse(wrapper_path);
+    let functions: Vec<CXCursor> = children(unsafe { clang_getTranslationUnitCursor(tu.tu) })
+        .into_iter()
+        .filter(|&cursor| unsafe {
+            clang_getCursorKind(cursor) == CXCursor_FunctionDecl
+                && clang_isCursorDefinition(cursor) != 0
+                && clang_Cursor_getStorageClass(cursor) == CX_SC_Static
+                && clang_Cursor_isFunctionInlined(cursor) != 0
+        })
+        .collect();
+
+    let mut text = String::new();
+    for header in headers {
+        let canonical = header.canonicalize().unwrap();
+        let module = module_name(c_src_path, header);
+        text.push_str(&format!(
+            "/// Transpiled from `{}`.\npub mod {module} {{\n",
+            header.display()
+        ));
+        for &function in &functions {
+            if file_of(function).as_ref() != Some(&canonical) {
+                continue;
+            }
+            match Translator::new(tu.tu).function(function) {
+                Ok(rust) => text.push_str(&rust),
+                Err(reason) => println!(
+                    "cargo:warning=not transpiled `{module}::{}`: {reason}",
+                    spelling(function)
+                ),
+            }
+        }
+        text.push_str("}\n");
+    }
+
+    let file = syn::parse_file(&text)
+        .unwrap_or_else(|err| panic!("transpiled code doesn't parse: {err}\n{text}"));
+    let path = out_path.join("transpiled.rs");
+    fs::write(&path, prettyplease::unparse(&file))
+        .expect("Couldn't write the transpiled functions!");
+    path
+}
+
+/// A parsed translation unit, disposed of on drop.
+struct TranslationUnit {
+    index: CXIndex,
+    tu: CXTranslationUnit,
+}
+
+impl TranslationUnit {
+    /// Parses `path` as C, with the system include paths bindgen would use.
+    fn parse(path: &Path) -> TranslationUnit {
+        let mut args = vec!["-xc".to_owned()];
+        if let Some(paths) = support::Clang::find(None, &[]).and_then(|clang| clang.c_search_paths)
+        {
+            for path in paths {
+                args.push("-isystem".to_owned());
+                args.push(path.display().to_string());
+            }
+        }
+        let args: Vec<CString> = args
+            .into_iter()
+            .map(|arg| CString::new(arg).unwrap())
+            .collect();
+        let arg_ptrs: Vec<*const c_char> = args.iter().map(|arg| arg.as_ptr()).collect();
+        let file = CString::new(path.to_str().unwrap()).unwrap();
+        unsafe {
+            let index = clang_createIndex(0, 0);
+            let tu = clang_parseTranslationUnit(
+                index,
+                file.as_ptr(),
+                arg_ptrs.as_ptr(),
+                arg_ptrs.len() as c_int,
+                ptr::null_mut(),
+                0,
+                CXTranslationUnit_None,
+            );
+            if tu.is_null() {
+                clang_disposeIndex(index);
+                panic!("libclang couldn't parse {}", path.display());
+            }
+            TranslationUnit { index, tu }
+        }
+    }
+}
+
+impl Drop for TranslationUnit {
+    fn drop(&mut self) {
+        unsafe {
+            clang_disposeTranslationUnit(self.tu);
+            clang_disposeIndex(self.index);
+        }
+    }
+}
+
+/// The C types a translated function may use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Ty {
+    Int { bits: i64, signed: bool },
+    Float { bits: i64 },
+    Bool,
+}
+
+impl Ty {
+    fn of(ty: CXType) -> Result<Ty, String> {
+        let canonical = unsafe { clang_getCanonicalType(ty) };
+        let bits = unsafe { clang_Type_getSizeOf(canonical) } * 8;
+        match canonical.kind {
+            CXType_Bool => Ok(Ty::Bool),
+            CXType_Char_S | CXType_SChar | CXType_Short | CXType_Int | CXType_Long
+            | CXType_LongLong => Ok(Ty::Int { bits, signed: true }),
+            CXType_Char_U | CXType_UChar | CXType_UShort | CXType_UInt | CXType_ULong
+            | CXType_ULongLong => Ok(Ty::Int {
+                bits,
+                signed: false,
+            }),
+            CXType_Float | CXType_Double => Ok(Ty::Float { bits }),
+            _ => Err(format!("uses the type `{}`", type_spelling(ty))),
+        }
+    }
+
+    fn rust(self) -> String {
+        match self {
+            Ty::Int { bits, signed: true } => format!("i{bits}"),
+            Ty::Int {
+                bits,
+                signed: false,
+            } => format!("u{bits}"),
+            Ty::Float { bits } => format!("f{bits}"),
+            Ty::Bool => "bool".to_owned(),
+        }
+    }
+}
+
+/// Translates one function. Every expression it returns is a path, a
+/// literal, a method call or parenthesized, so it can be used as an operand
+/// as is.
+struct Translator {
+    tu: CXTranslationUnit,
+    /// The parameters and locals in scope.
+    names: Vec<String>,
+}
+
+impl Translator {
+    fn new(tu: CXTranslationUnit) -> Translator {
+        Translator {
+            tu,
+            names: Vec::new(),
+        }
+    }
+
+    fn function(&mut self, function: CXCursor) -> Result<String, String> {
+        let ty = unsafe { clang_getCursorType(function) };
+        let result = unsafe { clang_getResultType(ty) };
+        let output = if result.kind == CXType_Void {
+            String::new()
+        } else {
+            format!(" -> {}", Ty::of(result)?.rust())
+        };
+
+        let mut params = Vec::new();
+        for i in 0..unsafe { clang_Cursor_getNumArguments(function) } {
+            let param = unsafe { clang_Cursor_getArgument(function, i as c_uint) };
+            let name = spelling(param);
+            if name.is_empty() {
+                return Err(format!("parameter {} has no name", i + 1));
+            }
+            let ty = Ty::of(unsafe { clang_getCursorType(param) })?;
+            params.push(format!("{}: {}", ident(&name), ty.rust()));
+            self.names.push(name);
+        }
+
+        let body = children(function)
+            .into_iter()
+            .find(|&child| unsafe { clang_getCursorKind(child) } == CXCursor_CompoundStmt)
+            .ok_or("it has no body")?;
+        Ok(format!(
+            "#[inline]\npub fn {}({}){output} {{\n{}}}\n",
+            spelling(function),
+            params.join(", "),
+            self.statement(body)?
+        ))
+    }
+
+    fn statement(&mut self, cursor: CXCursor) -> Result<String, String> {
+        let kids = children(cursor);
+        match unsafe { clang_getCursorKind(cursor) } {
+            CXCursor_CompoundStmt => {
+                let mut block = String::new();
+                for kid in kids {
+                    block.push_str(&self.statement(kid)?);
+                }
+                Ok(block)
+            }
+            CXCursor_ReturnStmt => match kids.first() {
+                Some(&value) => Ok(format!("return {};\n", self.expr(value)?)),
+                None => Ok("return;\n".to_owned()),
+            },
+            CXCursor_IfStmt => {
+                let cond = self.condition(kids[0])?;
+                let then = self.statement(kids[1])?;
+                match kids.get(2) {
+                    Some(&otherwise) => Ok(format!(
+                        "if {cond} {{\n{then}}} else {{\n{}}}\n",
+                        self.statement(otherwise)?
+                    )),
+                    None => Ok(format!("if {cond} {{\n{then}}}\n")),
+                }
+            }
+            CXCursor_DeclStmt => {
+                let mut lets = String::new();
+                for var in kids {
+                    if unsafe { clang_getCursorKind(var) } != CXCursor_VarDecl {
+                        return Err(format!("declares `{}`", tokens(self.tu, var).join(" ")));
+                    }
+                    let name = spelling(var);
+                    let ty = Ty::of(unsafe { clang_getCursorType(var) })?;
+                    let init = children(var)
+                        .into_iter()
+                        .rfind(|&kid| unsafe { clang_isExpression(clang_getCursorKind(kid)) } != 0)
+                        .ok_or_else(|| format!("`{name}` has no initializer"))?;
+                    lets.push_str(&format!(
+                        "let {}: {} = {};\n",
+                        ident(&name),
+                        ty.rust(),
+                        self.expr(init)?
+                    ));
+                    self.names.push(name);
+                }
+                Ok(lets)
+            }
+            CXCursor_NullStmt => Ok(String::new()),
+            _ => Err(format!(
+                "unsupported statement `{}`",
+                tokens(self.tu, cursor).join(" ")
+            )),
+        }
+    }
+
+    fn expr(&self, cursor: CXCursor) -> Result<String, String> {
+        let ty = Ty::of(unsafe { clang_getCursorType(cursor) })?;
+        if let Some(predicate) = self.predicate(cursor)? {
+            return Ok(format!("{}::from({predicate})", ty.rust()));
+        }
+        let kids = children(cursor);
+        match unsafe { clang_getCursorKind(cursor) } {
+            CXCursor_IntegerLiteral | CXCursor_FloatingLiteral | CXCursor_CharacterLiteral => {
+                literal(cursor, ty)
+            }
+            CXCursor_ParenExpr => self.expr(kids[0]),
+            CXCursor_DeclRefExpr => {
+                let name = spelling(cursor);
+                if !self.names.contains(&name) {
+                    return Err(format!("refers to `{name}`"));
+                }
+                Ok(ident(&name))
+            }
+            // Implicit conversions show up as unexposed expressions.
+            CXCursor_UnexposedExpr | CXCursor_CStyleCastExpr => {
+                let inner = *kids.last().ok_or("empty cast")?;
+                let from = Ty::of(unsafe { clang_getCursorType(inner) })?;
+                Ok(convert(self.expr(inner)?, from, ty))
+            }
+            CXCursor_UnaryOperator => {
+                let op = tokens(self.tu, cursor)
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default();
+                let value = self.expr(kids[0])?;
+                match (op.as_str(), ty) {
+                    ("+", _) => Ok(value),
+                    ("-", Ty::Int { .. }) => Ok(format!("{value}.wrapping_neg()")),
+                    ("-", _) => Ok(format!("(-{value})")),
+                    ("~", Ty::Int { .. }) => Ok(format!("(!{value})")),
+                    _ => Err(format!("uses the operator `{op}`")),
+                }
+            }
+            CXCursor_BinaryOperator => {
+                let op = operator_after(self.tu, cursor, kids[0]);
+                let operand_ty = Ty::of(unsafe { clang_getCursorType(kids[0]) })?;
+                let (a, b) = (self.expr(kids[0])?, self.expr(kids[1])?);
+                let wrapping = |method: &str| format!("{a}.wrapping_{method}({b})");
+                match (op.as_str(), operand_ty) {
+                    ("+", Ty::Int { .. }) => Ok(wrapping("add")),
+                    ("-", Ty::Int { .. }) => Ok(wrapping("sub")),
+                    ("*", Ty::Int { .. }) => Ok(wrapping("mul")),
+                    ("/", Ty::Int { .. }) => Ok(wrapping("div")),
+                    ("%", Ty::Int { .. }) => Ok(wrapping("rem")),
+                    ("<<", Ty::Int { .. }) => Ok(format!("{a}.wrapping_shl({b} as u32)")),
+                    (">>", Ty::Int { .. }) => Ok(format!("{a}.wrapping_shr({b} as u32)")),
+                    ("+" | "-" | "*" | "/", Ty::Float { .. }) => Ok(format!("({a} {op} {b})")),
+                    ("&" | "|" | "^", Ty::Int { .. } | Ty::Bool) => Ok(format!("({a} {op} {b})")),
+                    _ => Err(format!("uses the operator `{op}`")),
+                }
+            }
+            CXCursor_ConditionalOperator => Ok(format!(
+                "(if {} {{ {} }} else {{ {} }})",
+                self.condition(kids[0])?,
+                self.expr(kids[1])?,
+                self.expr(kids[2])?
+            )),
+            _ => Err(format!(
+                "unsupported expression `{}`",
+                tokens(self.tu, cursor).join(" ")
+            )),
+        }
+    }
+
+    /// Translates `!`, a comparison or a logical operator to a `bool`
+    /// expression, or returns `None` for any other expression.
+    fn predicate(&self, cursor: CXCursor) -> Result<Option<String>, String> {
+        let kids = children(cursor);
+        match unsafe { clang_getCursorKind(cursor) } {
+            CXCursor_ParenExpr => self.predicate(kids[0]),
+            CXCursor_UnaryOperator => {
+                let op = tokens(self.tu, cursor).into_iter().next();
+                match op.as_deref() {
+                    Some("!") => Ok(Some(format!("!{}", self.condition(kids[0])?))),
+                    _ => Ok(None),
+                }
+            }
+            CXCursor_BinaryOperator => match operator_after(self.tu, cursor, kids[0]).as_str() {
+                op @ ("<" | ">" | "<=" | ">=" | "==" | "!=") => Ok(Some(format!(
+                    "({} {op} {})",
+                    self.expr(kids[0])?,
+                    self.expr(kids[1])?
+                ))),
+                op @ ("&&" | "||") => Ok(Some(format!(
+                    "({} {op} {})",
+                    self.condition(kids[0])?,
+                    self.condition(kids[1])?
+                ))),
+                _ => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Translates a C condition, which is true when it isn't zero.
+    fn condition(&self, cursor: CXCursor) -> Result<String, String> {
+        if let Some(predicate) = self.predicate(cursor)? {
+            return Ok(predicate);
+        }
+        let ty = Ty::of(unsafe { clang_getCursorType(cursor) })?;
+        let value = self.expr(cursor)?;
+        Ok(match ty {
+            Ty::Bool => value,
+            Ty::Int { .. } => format!("({value} != 0)"),
+            Ty::Float { .. } => format!("({value} != 0.0)"),
+        })
+    }
+}
+
+/// Converts `value` the way C converts between arithmetic types. Float to
+/// integer conversions saturate, where C leaves out-of-range values
+/// undefined.
+fn convert(value: String, from: Ty, to: Ty) -> String {
+    match (from, to) {
+        _ if from == to => value,
+        (Ty::Int { .. }, Ty::Bool) => format!("({value} != 0)"),
+        (Ty::Float { .. }, Ty::Bool) => format!("({value} != 0.0)"),
+        _ => format!("({value} as {})", to.rust()),
+    }
+}
+
+/// Spells out the value of a literal, or of the macro it was written as.
+fn literal(cursor: CXCursor, ty: Ty) -> Result<String, String> {
+    unsafe {
+        let result = clang_Cursor_Evaluate(cursor);
+        if result.is_null() {
+            return Err("can't evaluate a literal".to_owned());
+        }
+        let text = match (clang_EvalResult_getKind(result), ty) {
+            (CXEval_Int, Ty::Int { signed: false, .. }) => Ok(format!(
+                "{}_{}",
+                clang_EvalResult_getAsUnsigned(result),
+                ty.rust()
+            )),
+            (CXEval_Int, Ty::Int { .. }) => Ok(format!(
+                "{}_{}",
+                clang_EvalResult_getAsLongLong(result),
+                ty.rust()
+            )),
+            (CXEval_Int, Ty::Bool) => Ok((clang_EvalResult_getAsLongLong(result) != 0).to_string()),
+            (CXEval_Float, Ty::Float { bits }) => {
+                let value = clang_EvalResult_getAsDouble(result);
+                if !value.is_finite() {
+                    Err(format!("the literal {value} is not finite"))
+                } else if bits == 32 {
+                    Ok(format!("{:?}_f32", value as f32))
+                } else {
+                    Ok(format!("{value:?}_f64"))
+                }
+            }
+            _ => Err("can't evaluate a literal".to_owned()),
+        };
+        clang_EvalResult_dispose(result);
+        text
+    }
+}
+
+/// Escapes C names that are Rust keywords.
+fn ident(name: &str) -> String {
+    const KEYWORDS: [&str; 34] = [
+        "abstract",
+        "as",
+        "async",
+        "await",
+        "become",
+        "box",
+        "dyn",
+        "final",
+        "fn",
+        "gen",
+        "impl",
+        "in",
+        "let",
+        "loop",
+        "macro",
+        "match",
+        "mod",
+        "move",
+        "mut",
+        "override",
+        "priv",
+        "pub",
+        "ref",
+        "trait",
+        "try",
+        "type",
+        "typeof",
+        "unsafe",
+        "unsized",
+        "use",
+        "virtual",
+        "where",
+        "yield",
+        "macro_rules",
+    ];
+    match name {
+        // These can't be raw identifiers.
+        "crate" | "self" | "Self" | "super" => format!("{name}_"),
+        _ if KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_owned(),
+    }
+}
+
+fn children(cursor: CXCursor) -> Vec<CXCursor> {
+    extern "C" fn push(child: CXCursor, _: CXCursor, data: CXClientData) -> CXChildVisitResult {
+        unsafe { (*(data as *mut Vec<CXCursor>)).push(child) };
+        CXChildVisit_Continue
+    }
+    let mut kids = Vec::new();
+    unsafe { clang_visitChildren(cursor, push, &mut kids as *mut Vec<CXCursor> as *mut c_void) };
+    kids
+}
+
+fn tokens(tu: CXTranslationUnit, cursor: CXCursor) -> Vec<String> {
+    unsafe {
+        let mut tokens = ptr::null_mut();
+        let mut count = 0;
+        clang_tokenize(tu, clang_getCursorExtent(cursor), &mut tokens, &mut count);
+        let spelled = (0..count as usize)
+            .map(|i| string(clang_getTokenSpelling(tu, *tokens.add(i))))
+            .collect();
+        clang_disposeTokens(tu, tokens, count);
+        spelled
+    }
+}
+
+/// The first token of `cursor` after the end of its child `lhs`, which is the
+/// operator of a binary expression. Source offsets are compared, because
+/// some libclang versions include the next token in an extent.
+fn operator_after(tu: CXTranslationUnit, cursor: CXCursor, lhs: CXCursor) -> String {
+    unsafe {
+        let lhs_end = offset(clang_getRangeEnd(clang_getCursorExtent(lhs)));
+        let mut tokens = ptr::null_mut();
+        let mut count = 0;
+        clang_tokenize(tu, clang_getCursorExtent(cursor), &mut tokens, &mut count);
+        let op = (0..count as usize)
+            .map(|i| *tokens.add(i))
+            .find(|&token| offset(clang_getRangeStart(clang_getTokenExtent(tu, token))) >= lhs_end)
+            .map(|token| string(clang_getTokenSpelling(tu, token)))
+            .unwrap_or_default();
+        clang_disposeTokens(tu, tokens, count);
+        op
+    }
+}
+
+fn offset(location: CXSourceLocation) -> c_uint {
+    let mut offset = 0;
+    unsafe {
+        clang_getFileLocation(
+            location,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut offset,
+        )
+    };
+    offset
+}
+
+/// The canonical path of the file `cursor` is in.
+fn file_of(cursor: CXCursor) -> Option<PathBuf> {
+    unsafe {
+        let mut file = ptr::null_mut();
+        clang_getFileLocation(
+            clang_getCursorLocation(cursor),
+            &mut file,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        if file.is_null() {
+            return None;
+        }
+        PathBuf::from(string(clang_getFileName(file)))
+            .canonicalize()
+            .ok()
+    }
+}
+
+fn spelling(cursor: CXCursor) -> String {
+    string(unsafe { clang_getCursorSpelling(cursor) })
+}
+
+fn type_spelling(ty: CXType) -> String {
+    string(unsafe { clang_getTypeSpelling(ty) })
+}
+
+fn string(text: CXString) -> String {
+    unsafe {
+        let ptr = clang_getCString(text);
+        let owned = if ptr.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(ptr).to_string_lossy().into_owned()
+        };
+        clang_disposeString(text);
+        owned
+    }
+}
@@ -0,0 +1,30 @@
+#include "bitfields.h"
+
+Instr instr_encode(unsigned int opcode, unsigned int dest, unsigned int src, unsigned int imm) {
+    Instr instr;
+    instr.opcode = opcode;
+    instr.dest = dest;
+    instr.src = src;
+    instr.imm = imm;
+    return instr;
+}
+
+unsigned int instr_opcode(Instr instr) {
+    return instr.opcode;
+}
+
+unsigned int instr_imm(Instr instr) {
+    return instr.imm;
+}
+
+void instr_set_dest(Instr* instr, unsigned int dest) {
+    instr->dest = dest;
+}
+
+uint32_t instr_sum_imm(const Instr* instrs, size_t len) {
+    uint32_t sum = 0;
+    for (size_t i = 0; i < len; i++) {
+        sum += instrs[i].imm;
+    }
+    return sum;
+}
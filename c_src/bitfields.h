@@ -0,0 +1,35 @@
+#ifndef BITFIELDS_H
+#define BITFIELDS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// An instruction word packed into bitfields. Clang reads and writes each
+// field with a shift and a mask of the 32-bit unit; bindgen gives Rust
+// accessor methods that do the same over a byte array.
+typedef struct {
+    unsigned int opcode : 6;
+    unsigned int dest : 5;
+    unsigned int src : 5;
+    unsigned int imm : 16;
+} Instr;
+
+// The fields keep the low bits of each argument, as assigning to a bitfield
+// does.
+Instr instr_encode(unsigned int opcode, unsigned int dest, unsigned int src, unsigned int imm);
+unsigned int instr_opcode(Instr instr);
+unsigned int instr_imm(Instr instr);
+
+/**
+ * @ir ref(instr)
+ */
+void instr_set_dest(Instr* instr, unsigned int dest);
+
+/**
+ * Sums the `imm` fields of `instrs`, wrapping around.
+ *
+ * @ir slice(instrs, len)
+ */
+uint32_t instr_sum_imm(const Instr* instrs, size_t len);
+
+#endif
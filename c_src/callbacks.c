@@ -0,0 +1,40 @@
+#include "callbacks.h"
+
+static void swap(int* a, int* b) {
+    int tmp = *a;
+    *a = *b;
+    *b = tmp;
+}
+
+void sort_ints(int* values, size_t len,
+               int (*compare)(const int* a, const int* b, void* context),
+               void* context) {
+    while (len > 1) {
+        // Partition around the last element.
+        size_t pivot = 0;
+        for (size_t i = 0; i + 1 < len; i++) {
+            if (compare(&values[i], &values[len - 1], context) < 0) {
+                swap(&values[i], &values[pivot]);
+                pivot++;
+            }
+        }
+        swap(&values[pivot], &values[len - 1]);
+
+        // Recurse into the smaller side and loop on the larger one.
+        size_t right = len - pivot - 1;
+        if (pivot < right) {
+            sort_ints(values, pivot, compare, context);
+            values += pivot + 1;
+            len = right;
+        } else {
+            sort_ints(values + pivot + 1, right, compare, context);
+            len = pivot;
+        }
+    }
+}
+
+void map_array(int* values, size_t len, int (*f)(int x, void* context), void* context) {
+    for (size_t i = 0; i < len; i++) {
+        values[i] = f(values[i], context);
+    }
+}
@@ -0,0 +1,32 @@
+#ifndef CALLBACKS_H
+#define CALLBACKS_H
+
+#include <stddef.h>
+
+// Kernels that call back into the caller. The callbacks take a context
+// pointer, like `qsort_r`, so that Rust can pass closures through them.
+// `@ir unwind` lets a panic in them unwind through these functions, which
+// is why ir_cc.toml compiles callbacks.c with `-fexceptions`.
+
+/**
+ * Sorts `values` in place, in the order `compare` gives: negative, zero or
+ * positive, as with `qsort`.
+ *
+ * @ir slice(values, len)
+ * @ir callback(compare, context)
+ * @ir unwind(compare)
+ */
+void sort_ints(int* values, size_t len,
+               int (*compare)(const int* a, const int* b, void* context),
+               void* context);
+
+/**
+ * Replaces each of `values` with `f` applied to it.
+ *
+ * @ir slice(values, len)
+ * @ir callback(f, context)
+ * @ir unwind(f)
+ */
+void map_array(int* values, size_t len, int (*f)(int x, void* context), void* context);
+
+#endif
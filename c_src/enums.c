@@ -0,0 +1,124 @@
+#include "enums.h"
+
+#include <limits.h>
+
+int shape_sides(Shape shape) {
+    switch (shape) {
+    case SHAPE_CIRCLE:
+        return 0;
+    case SHAPE_SQUARE:
+        return 4;
+    case SHAPE_TRIANGLE:
+        return 3;
+    case SHAPE_HEXAGON:
+        return 6;
+    }
+    return -1;
+}
+
+double shape_area(Shape shape, double size) {
+    switch (shape) {
+    case SHAPE_CIRCLE:
+        return 3.141592653589793 * size * size;
+    case SHAPE_SQUARE:
+        return size * size;
+    case SHAPE_TRIANGLE:
+        return 0.4330127018922193 * size * size;
+    case SHAPE_HEXAGON:
+        return 2.598076211353316 * size * size;
+    }
+    return 0.0;
+}
+
+Shape shape_next(Shape shape) {
+    switch (shape) {
+    case SHAPE_CIRCLE:
+        return SHAPE_SQUARE;
+    case SHAPE_SQUARE:
+        return SHAPE_TRIANGLE;
+    case SHAPE_TRIANGLE:
+        return SHAPE_HEXAGON;
+    case SHAPE_HEXAGON:
+        return SHAPE_CIRCLE;
+    }
+    return SHAPE_CIRCLE;
+}
+
+int apply_op(Op op, int a, int b) {
+    switch (op) {
+    case OP_ADD:
+        return a + b;
+    case OP_SUB:
+        return a - b;
+    case OP_MUL:
+        return a * b;
+    case OP_DIV:
+        return b != 0 ? a / b : 0;
+    case OP_NEG:
+        return -a;
+    }
+    return 0;
+}
+
+bool checked_apply_op(Op op, int a, int b, int* result) {
+    switch (op) {
+    case OP_ADD:
+        return __builtin_add_overflow(a, b, result);
+    case OP_SUB:
+        return __builtin_sub_overflow(a, b, result);
+    case OP_MUL:
+        return __builtin_mul_overflow(a, b, result);
+    case OP_DIV:
+        if (a == INT_MIN && b == -1) return true;
+        *result = b != 0 ? a / b : 0;
+        return false;
+    case OP_NEG:
+        return __builtin_sub_overflow(0, a, result);
+    }
+    *result = 0;
+    return false;
+}
+
+int wrapping_apply_op(Op op, int a, int b) {
+    int result = 0;
+    switch (op) {
+    case OP_ADD:
+        __builtin_add_overflow(a, b, &result);
+        break;
+    case OP_SUB:
+        __builtin_sub_overflow(a, b, &result);
+        break;
+    case OP_MUL:
+        __builtin_mul_overflow(a, b, &result);
+        break;
+    case OP_DIV:
+        if (a == INT_MIN && b == -1) return INT_MIN;
+        return b != 0 ? a / b : 0;
+    case OP_NEG:
+        __builtin_sub_overflow(0, a, &result);
+        break;
+    }
+    return result;
+}
+
+int saturating_apply_op(Op op, int a, int b) {
+    int result = 0;
+    switch (op) {
+    case OP_ADD:
+        if (__builtin_add_overflow(a, b, &result)) return a < 0 ? INT_MIN : INT_MAX;
+        break;
+    case OP_SUB:
+        if (__builtin_sub_overflow(a, b, &result)) return a < 0 ? INT_MIN : INT_MAX;
+        break;
+    case OP_MUL:
+        if (__builtin_mul_overflow(a, b, &result)) return (a < 0) != (b < 0) ? INT_MIN : INT_MAX;
+        break;
+    case OP_DIV:
+        if (a == INT_MIN && b == -1) return INT_MAX;
+        return b != 0 ? a / b : 0;
+    case OP_NEG:
+        if (__builtin_sub_overflow(0, a, &result)) return INT_MAX;
+        break;
+    }
+    return result;
+}
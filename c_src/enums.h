@@ -0,0 +1,41 @@
+#ifndef ENUMS_H
+#define ENUMS_H
+
+#include <stdbool.h>
+
+// Kernels that switch over enums: `Shape` has dense values, which suit a
+// jump table or a lookup table, and `Op` has sparse ones.
+typedef enum {
+    SHAPE_CIRCLE,
+    SHAPE_SQUARE,
+    SHAPE_TRIANGLE,
+    SHAPE_HEXAGON,
+} Shape;
+
+typedef enum {
+    OP_ADD = 1,
+    OP_SUB = 2,
+    OP_MUL = 4,
+    OP_DIV = 8,
+    OP_NEG = 16,
+} Op;
+
+int shape_sides(Shape shape);
+double shape_area(Shape shape, double size);
+Shape shape_next(Shape shape);
+int apply_op(Op op, int a, int b);
+
+// `apply_op` overflows on `OP_ADD`, `OP_SUB` and `OP_MUL` with large
+// operands, on `INT_MIN / -1` and on `-INT_MIN`. These variants define what
+// happens then. Dividing by zero still gives 0.
+
+/**
+ * Returns whether the operation overflows.
+ *
+ * @ir checked(result)
+ */
+bool checked_apply_op(Op op, int a, int b, int* result);
+int wrapping_apply_op(Op op, int a, int b);
+int saturating_apply_op(Op op, int a, int b);
+
+#endif
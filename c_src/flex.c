@@ -0,0 +1,15 @@
+#include "flex.h"
+
+double series_sum(const Series* s) {
+    double sum = 0.0;
+    for (size_t i = 0; i < s->len; i++) {
+        sum += s->data[i];
+    }
+    return sum;
+}
+
+void series_scale(Series* s, double k) {
+    for (size_t i = 0; i < s->len; i++) {
+        s->data[i] *= k;
+    }
+}
@@ -0,0 +1,21 @@
+#ifndef FLEX_H
+#define FLEX_H
+
+#include <stddef.h>
+
+// A length followed by a flexible array member: the elements live in the
+// same allocation, right after the header. Bindgen types `data` as a
+// zero-length `__IncompleteArrayField`, so Rust has to allocate the header
+// and the elements together by hand.
+typedef struct {
+    size_t len;
+    double data[];
+} Series;
+
+// No @ir annotation fits `s`: a Rust reference to a `Series` covers only
+// the header, so the functions get no safe wrapper.
+double series_sum(const Series* s);
+
+void series_scale(Series* s, double k);
+
+#endif
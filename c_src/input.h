@@ -1,12 +1,59 @@
-#ifndef MATH_OPS_H
-#define MATH_OPS_H
+#ifndef INPUT_H
+#define INPUT_H
+
+#include <limits.h>
+#include <stdbool.h>
 
 static inline int inc(int x) {
     return x + 1;
 }
 
 static inline int dec(int x) {
-    return x + 1;
+    return x - 1;
+}
+
+// `inc` and `dec` overflow on `INT_MAX` and `INT_MIN`, which is undefined
+// behavior. These variants define it with the `__builtin_*_overflow`
+// functions of GCC and clang.
+
+/**
+ * Returns whether `x + 1` overflows.
+ *
+ * @ir checked(result)
+ */
+static inline bool checked_inc(int x, int* result) {
+    return __builtin_add_overflow(x, 1, result);
+}
+
+static inline int wrapping_inc(int x) {
+    int result;
+    __builtin_add_overflow(x, 1, &result);
+    return result;
+}
+
+static inline int saturating_inc(int x) {
+    int result;
+    return __builtin_add_overflow(x, 1, &result) ? INT_MAX : result;
+}
+
+/**
+ * Returns whether `x - 1` overflows.
+ *
+ * @ir checked(result)
+ */
+static inline bool checked_dec(int x, int* result) {
+    return __builtin_sub_overflow(x, 1, result);
+}
+
+static inline int wrapping_dec(int x) {
+    int result;
+    __builtin_sub_overflow(x, 1, &result);
+    return result;
+}
+
+static inline int saturating_dec(int x) {
+    int result;
+    return __builtin_sub_overflow(x, 1, &result) ? INT_MIN : result;
 }
 
-#endif
\ No newline at end of file
+#endif
@@ -0,0 +1,25 @@
+#include "macros.h"
+
+int buffer_bytes(void) {
+    return BUFFER_BYTES;
+}
+
+double scaled_buffer_bytes(void) {
+    return BUFFER_BYTES * SCALE;
+}
+
+unsigned int set_flag(unsigned int flags) {
+    return flags | FLAG_MASK;
+}
+
+int next_color(int color) {
+    switch (color) {
+    case COLOR_RED:
+        return COLOR_GREEN;
+    case COLOR_GREEN:
+        return COLOR_BLUE;
+    case COLOR_BLUE:
+        return COLOR_RED;
+    }
+    return -1;
+}
@@ -0,0 +1,58 @@
+#ifndef MACROS_H
+#define MACROS_H
+
+#include <stddef.h>
+
+// Constants spelled as `#define`s. Bindgen evaluates each one with its own
+// expression parser and binds the value as a `pub const`.
+#define BUFFER_LEN 256
+#define ELEM_SIZE 8
+#define BUFFER_BYTES (BUFFER_LEN * ELEM_SIZE)
+#define FLAG_MASK (1u << 7)
+#define SCALE 0.125
+#define MIN_TEMP (-40)
+#define MAX_TEMP 60
+
+// An enum spelled as a group of `#define`s, passed around as `int`.
+// `ir_bindgen.toml` gives the group one type.
+#define COLOR_RED 0
+#define COLOR_GREEN 1
+#define COLOR_BLUE 2
+
+static inline int clamp_int(int x, int lo, int hi) {
+    return x < lo ? lo : x > hi ? hi : x;
+}
+
+static inline int in_range(int x, int lo, int hi) {
+    return lo <= x && x <= hi;
+}
+
+// Function-like macros, which bindgen drops. build.rs writes each one out as
+// the `static inline` function in its `@ir inline` annotation.
+
+/**
+ * @ir inline(int clamp(int x, int lo, int hi))
+ */
+#define CLAMP(x, lo, hi) clamp_int((x), (lo), (hi))
+
+/**
+ * @ir inline(int clamp_temp(int t))
+ */
+#define CLAMP_TEMP(t) clamp_int((t), MIN_TEMP, MAX_TEMP)
+
+/**
+ * The byte offset of element `i` in the ring buffer.
+ *
+ * @ir inline(size_t buffer_offset(size_t i))
+ */
+#define BUFFER_OFFSET(i) (((i) % BUFFER_LEN) * ELEM_SIZE)
+
+// Functions whose results fold to constants at compile time.
+int buffer_bytes(void);
+double scaled_buffer_bytes(void);
+unsigned int set_flag(unsigned int flags);
+
+// The next of the three colors, or -1 for a value that is no color.
+int next_color(int color);
+
+#endif
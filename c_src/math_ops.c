@@ -0,0 +1,63 @@
+#include "math_ops.h"
+
+int32_t factorial(int32_t n) {
+    if (n <= 1) return 1;
+    int32_t result = 1;
+    for (int32_t i = 2; i <= n; i++) {
+        result *= i;
+    }
+    return result;
+}
+
+double vector_dot_product(const double* a, const double* b, size_t len) {
+    double sum = 0.0;
+    for (size_t i = 0; i < len; i++) {
+        sum += a[i] * b[i];
+    }
+    return sum;
+}
+
+bool is_prime(uint32_t n) {
+    if (n <= 1) return false;
+    if (n <= 3) return true;
+    if (n % 2 == 0 || n % 3 == 0) return false;
+
+    for (uint32_t i = 5; i <= n / i; i += 6) {
+        if (n % i == 0 || n % (i + 2) == 0) {
+            return false;
+        }
+    }
+    return true;
+}
+
+void matrix_multiply_2x2(const double a[4], const double b[4], double result[4]) {
+    result[0] = a[0] * b[0] + a[1] * b[2];
+    result[1] = a[0] * b[1] + a[1] * b[3];
+    result[2] = a[2] * b[0] + a[3] * b[2];
+    result[3] = a[2] * b[1] + a[3] * b[3];
+}
+
+bool checked_factorial(int32_t n, int32_t* result) {
+    int32_t product = 1;
+    for (int32_t i = 2; i <= n; i++) {
+        if (__builtin_mul_overflow(product, i, &product)) return true;
+    }
+    *result = product;
+    return false;
+}
+
+int32_t wrapping_factorial(int32_t n) {
+    int32_t result = 1;
+    for (int32_t i = 2; i <= n; i++) {
+        __builtin_mul_overflow(result, i, &result);
+    }
+    return result;
+}
+
+int32_t saturating_factorial(int32_t n) {
+    int32_t result = 1;
+    for (int32_t i = 2; i <= n; i++) {
+        if (__builtin_mul_overflow(result, i, &result)) return INT32_MAX;
+    }
+    return result;
+}
@@ -0,0 +1,36 @@
+#ifndef MATH_OPS_H
+#define MATH_OPS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Simple mathematical operations for comparison
+int32_t factorial(int32_t n);
+
+/**
+ * @ir slice(a, b, len)
+ */
+double vector_dot_product(const double* a, const double* b, size_t len);
+
+bool is_prime(uint32_t n);
+
+/**
+ * @ir array(a, 4)
+ * @ir array(b, 4)
+ * @ir out(result, 4)
+ */
+void matrix_multiply_2x2(const double a[4], const double b[4], double result[4]);
+
+// `factorial` overflows from 13 on. These variants define what happens then.
+
+/**
+ * Returns whether `n!` overflows.
+ *
+ * @ir checked(result)
+ */
+bool checked_factorial(int32_t n, int32_t* result);
+int32_t wrapping_factorial(int32_t n);
+int32_t saturating_factorial(int32_t n);
+
+#endif
@@ -0,0 +1,56 @@
+#include "simd.h"
+
+#include <emmintrin.h>
+#include <string.h>
+
+void saxpy_sse2(float a, const float* x, float* y, size_t n) {
+    __m128 va = _mm_set1_ps(a);
+    size_t i = 0;
+    for (; i + 4 <= n; i += 4) {
+        __m128 vx = _mm_loadu_ps(x + i);
+        __m128 vy = _mm_loadu_ps(y + i);
+        _mm_storeu_ps(y + i, _mm_add_ps(_mm_mul_ps(va, vx), vy));
+    }
+    for (; i < n; i++) {
+        y[i] = a * x[i] + y[i];
+    }
+}
+
+float sum_sse2(const float* x, size_t n) {
+    __m128 acc = _mm_setzero_ps();
+    size_t i = 0;
+    for (; i + 4 <= n; i += 4) {
+        acc = _mm_add_ps(acc, _mm_loadu_ps(x + i));
+    }
+    float lanes[4];
+    _mm_storeu_ps(lanes, acc);
+    float sum = (lanes[0] + lanes[1]) + (lanes[2] + lanes[3]);
+    for (; i < n; i++) {
+        sum += x[i];
+    }
+    return sum;
+}
+
+// Counts the 8 bytes of `word` into the 4 tables, 2 bytes each.
+static void count_word(uint32_t tables[4][256], uint64_t word) {
+    for (int b = 0; b < 8; b++) {
+        tables[b % 4][(word >> (8 * b)) & 0xff]++;
+    }
+}
+
+void byte_histogram_sse2(const uint8_t* data, size_t len, uint32_t counts[256]) {
+    uint32_t tables[4][256];
+    memset(tables, 0, sizeof(tables));
+    size_t i = 0;
+    for (; i + 16 <= len; i += 16) {
+        __m128i bytes = _mm_loadu_si128((const __m128i*)(data + i));
+        count_word(tables, (uint64_t)_mm_cvtsi128_si64(bytes));
+        count_word(tables, (uint64_t)_mm_cvtsi128_si64(_mm_unpackhi_epi64(bytes, bytes)));
+    }
+    for (; i < len; i++) {
+        tables[0][data[i]]++;
+    }
+    for (int v = 0; v < 256; v++) {
+        counts[v] = tables[0][v] + tables[1][v] + tables[2][v] + tables[3][v];
+    }
+}
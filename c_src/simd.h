@@ -0,0 +1,62 @@
+#ifndef SIMD_H
+#define SIMD_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// The same kernels written with x86 intrinsics, in two widths: SSE2, which
+// every x86-64 CPU has, in simd.c, and AVX2 in simd_avx2.c. That file is
+// compiled with `-mavx2` (see ir_cc.toml), so its functions only run on CPUs
+// with AVX2, which their safe wrappers check. Each function handles the
+// elements that don't fill a vector one by one.
+
+/**
+ * `y = a * x + y`, 4 elements at a time.
+ *
+ * @ir slice(x, y, n)
+ */
+void saxpy_sse2(float a, const float* x, float* y, size_t n);
+
+/**
+ * `y = a * x + y`, 8 elements at a time.
+ *
+ * @ir slice(x, y, n)
+ * @ir target_feature(avx2)
+ */
+void saxpy_avx2(float a, const float* x, float* y, size_t n);
+
+/**
+ * The sum of `x`, over 4 lanes that are added up at the end:
+ * `(l0 + l1) + (l2 + l3)`, then the rest of `x` in order.
+ *
+ * @ir slice(x, n)
+ */
+float sum_sse2(const float* x, size_t n);
+
+/**
+ * The sum of `x`, over 8 lanes that are added up pairwise at the end.
+ *
+ * @ir slice(x, n)
+ * @ir target_feature(avx2)
+ */
+float sum_avx2(const float* x, size_t n);
+
+/**
+ * Counts each byte value in `data`. Loads 16 bytes at a time and counts them
+ * into 4 tables, so that runs of the same byte don't wait on each other.
+ *
+ * @ir slice(data, len)
+ * @ir out(counts, 256)
+ */
+void byte_histogram_sse2(const uint8_t* data, size_t len, uint32_t counts[256]);
+
+/**
+ * `byte_histogram_sse2`, loading 32 bytes at a time.
+ *
+ * @ir slice(data, len)
+ * @ir out(counts, 256)
+ * @ir target_feature(avx2)
+ */
+void byte_histogram_avx2(const uint8_t* data, size_t len, uint32_t counts[256]);
+
+#endif
@@ -0,0 +1,59 @@
+#include "simd.h"
+
+#include <immintrin.h>
+#include <string.h>
+
+void saxpy_avx2(float a, const float* x, float* y, size_t n) {
+    __m256 va = _mm256_set1_ps(a);
+    size_t i = 0;
+    for (; i + 8 <= n; i += 8) {
+        __m256 vx = _mm256_loadu_ps(x + i);
+        __m256 vy = _mm256_loadu_ps(y + i);
+        _mm256_storeu_ps(y + i, _mm256_add_ps(_mm256_mul_ps(va, vx), vy));
+    }
+    for (; i < n; i++) {
+        y[i] = a * x[i] + y[i];
+    }
+}
+
+float sum_avx2(const float* x, size_t n) {
+    __m256 acc = _mm256_setzero_ps();
+    size_t i = 0;
+    for (; i + 8 <= n; i += 8) {
+        acc = _mm256_add_ps(acc, _mm256_loadu_ps(x + i));
+    }
+    float lanes[8];
+    _mm256_storeu_ps(lanes, acc);
+    float sum = ((lanes[0] + lanes[1]) + (lanes[2] + lanes[3]))
+        + ((lanes[4] + lanes[5]) + (lanes[6] + lanes[7]));
+    for (; i < n; i++) {
+        sum += x[i];
+    }
+    return sum;
+}
+
+// Counts the 8 bytes of `word` into the 4 tables, 2 bytes each.
+static void count_word(uint32_t tables[4][256], uint64_t word) {
+    for (int b = 0; b < 8; b++) {
+        tables[b % 4][(word >> (8 * b)) & 0xff]++;
+    }
+}
+
+void byte_histogram_avx2(const uint8_t* data, size_t len, uint32_t counts[256]) {
+    uint32_t tables[4][256];
+    memset(tables, 0, sizeof(tables));
+    size_t i = 0;
+    for (; i + 32 <= len; i += 32) {
+        __m256i bytes = _mm256_loadu_si256((const __m256i*)(data + i));
+        count_word(tables, (uint64_t)_mm256_extract_epi64(bytes, 0));
+        count_word(tables, (uint64_t)_mm256_extract_epi64(bytes, 1));
+        count_word(tables, (uint64_t)_mm256_extract_epi64(bytes, 2));
+        count_word(tables, (uint64_t)_mm256_extract_epi64(bytes, 3));
+    }
+    for (; i < len; i++) {
+        tables[0][data[i]]++;
+    }
+    for (int v = 0; v < 256; v++) {
+        counts[v] = tables[0][v] + tables[1][v] + tables[2][v] + tables[3][v];
+    }
+}
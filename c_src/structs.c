@@ -0,0 +1,51 @@
+#include "structs.h"
+
+Vec2 vec2_add(Vec2 a, Vec2 b) {
+    Vec2 sum = { a.x + b.x, a.y + b.y };
+    return sum;
+}
+
+Sample sample_scale(Sample s, float k) {
+    s.weight *= k;
+    s.value *= k;
+    return s;
+}
+
+Block block_add(Block a, Block b) {
+    Block sum;
+    for (int i = 0; i < 8; i++) {
+        sum.values[i] = a.values[i] + b.values[i];
+    }
+    return sum;
+}
+
+Block block_splat(double x) {
+    Block block;
+    for (int i = 0; i < 8; i++) {
+        block.values[i] = x;
+    }
+    return block;
+}
+
+float vec2_dot(const Vec2* a, const Vec2* b) {
+    return a->x * b->x + a->y * b->y;
+}
+
+void sample_scale_in_place(Sample* s, float k) {
+    s->weight *= k;
+    s->value *= k;
+}
+
+double block_sum(const Block* b) {
+    double sum = 0.0;
+    for (int i = 0; i < 8; i++) {
+        sum += b->values[i];
+    }
+    return sum;
+}
+
+void block_add_into(const Block* a, const Block* b, Block* result) {
+    for (int i = 0; i < 8; i++) {
+        result->values[i] = a->values[i] + b->values[i];
+    }
+}
@@ -0,0 +1,54 @@
+#ifndef STRUCTS_H
+#define STRUCTS_H
+
+#include <stdint.h>
+
+// Structs of each x86-64 SysV argument class: two floats packed into one SSE
+// register, 16 bytes split across an integer and an SSE register, and 64
+// bytes passed in memory and returned through a hidden sret pointer.
+typedef struct {
+    float x;
+    float y;
+} Vec2;
+
+typedef struct {
+    int32_t id;
+    float weight;
+    double value;
+} Sample;
+
+typedef struct {
+    double values[8];
+} Block;
+
+// By value
+Vec2 vec2_add(Vec2 a, Vec2 b);
+Sample sample_scale(Sample s, float k);
+Block block_add(Block a, Block b);
+Block block_splat(double x);
+
+// By pointer
+/**
+ * @ir ref(a)
+ * @ir ref(b)
+ */
+float vec2_dot(const Vec2* a, const Vec2* b);
+
+/**
+ * @ir ref(s)
+ */
+void sample_scale_in_place(Sample* s, float k);
+
+/**
+ * @ir ref(b)
+ */
+double block_sum(const Block* b);
+
+/**
+ * @ir ref(a)
+ * @ir ref(b)
+ * @ir out(result)
+ */
+void block_add_into(const Block* a, const Block* b, Block* result);
+
+#endif
@@ -0,0 +1,43 @@
+#include "unions.h"
+
+Value value_int(int64_t i) {
+    Value v;
+    v.kind = VALUE_INT;
+    v.data.i = i;
+    return v;
+}
+
+Value value_float(double f) {
+    Value v;
+    v.kind = VALUE_FLOAT;
+    v.data.f = f;
+    return v;
+}
+
+Value value_pair(int32_t a, int32_t b) {
+    Value v;
+    v.kind = VALUE_PAIR;
+    v.data.pair.a = a;
+    v.data.pair.b = b;
+    return v;
+}
+
+double value_to_double(Value v) {
+    switch (v.kind) {
+    case VALUE_INT:
+        return (double)v.data.i;
+    case VALUE_FLOAT:
+        return v.data.f;
+    case VALUE_PAIR:
+        return (double)v.data.pair.a + (double)v.data.pair.b;
+    }
+    return 0.0;
+}
+
+double value_sum(const Value* values, size_t len) {
+    double sum = 0.0;
+    for (size_t i = 0; i < len; i++) {
+        sum += value_to_double(values[i]);
+    }
+    return sum;
+}
@@ -0,0 +1,43 @@
+#ifndef UNIONS_H
+#define UNIONS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// A tagged union: `kind` says which member of `data` holds the value. The
+// Rust port is an enum, which keeps the tag and the payload together.
+typedef enum {
+    VALUE_INT,
+    VALUE_FLOAT,
+    VALUE_PAIR,
+} ValueKind;
+
+typedef struct {
+    int32_t a;
+    int32_t b;
+} Pair;
+
+typedef union {
+    int64_t i;
+    double f;
+    Pair pair;
+} ValueData;
+
+typedef struct {
+    ValueKind kind;
+    ValueData data;
+} Value;
+
+Value value_int(int64_t i);
+Value value_float(double f);
+Value value_pair(int32_t a, int32_t b);
+
+// A pair converts to the sum of its halves, and an unknown kind to 0.
+double value_to_double(Value v);
+
+/**
+ * @ir slice(values, len)
+ */
+double value_sum(const Value* values, size_t len);
+
+#endif
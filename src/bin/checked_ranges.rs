@@ -0,0 +1,128 @@
+//! Calls each `c_ffi::checked` wrapper twice: with inputs whose types prove
+//! its preconditions, and with inputs that may break them.
+//!
+//! ```text
+//! cargo run --release --bin checked_ranges -- <n>...
+//! ```
+//!
+//! The wrappers live in the library, so without LTO every call here is a
+//! call into another crate that keeps its checks. With LTO they can be
+//! inlined, and the checks of the `_in_range` functions folded away; compare
+//! the branches of each pair with `ir_report --filter checked_ranges` on the
+//! IR of this binary, built in each LTO mode.
+
+use std::env;
+use std::process::ExitCode;
+
+use ir_comparison::c_ffi::checked::{self, PreconditionError};
+use ir_comparison::c_ffi::enums;
+
+type Result<T> = std::result::Result<T, PreconditionError>;
+
+#[inline(never)]
+fn inc_in_range(x: i16) -> Result<i32> {
+    checked::inc(x.into())
+}
+
+#[inline(never)]
+fn inc_any(x: i32) -> Result<i32> {
+    checked::inc(x)
+}
+
+#[inline(never)]
+fn dec_in_range(x: i16) -> Result<i32> {
+    checked::dec(x.into())
+}
+
+#[inline(never)]
+fn dec_any(x: i32) -> Result<i32> {
+    checked::dec(x)
+}
+
+#[inline(never)]
+fn factorial_in_range(n: u8) -> Result<i32> {
+    checked::factorial((n % 13).into())
+}
+
+#[inline(never)]
+fn factorial_any(n: i32) -> Result<i32> {
+    checked::factorial(n)
+}
+
+#[inline(never)]
+fn apply_op_in_range(a: i16, b: i16) -> Result<i32> {
+    checked::apply_op(enums::Op_OP_MUL, a.into(), b.into())
+}
+
+#[inline(never)]
+fn apply_op_any(a: i32, b: i32) -> Result<i32> {
+    checked::apply_op(enums::Op_OP_MUL, a, b)
+}
+
+#[inline(never)]
+fn vector_dot_product_in_range(a: &[f64; 8], b: &[f64; 8]) -> Result<f64> {
+    checked::vector_dot_product(a, b)
+}
+
+#[inline(never)]
+fn vector_dot_product_any(a: &[f64], b: &[f64]) -> Result<f64> {
+    checked::vector_dot_product(a, b)
+}
+
+#[inline(never)]
+fn matrix_multiply_2x2_in_range(a: &[f64; 4], b: &[f64; 4]) -> Result<[f64; 4]> {
+    let mut result = [0.0; 4];
+    checked::matrix_multiply_2x2(a, b, &mut result)?;
+    Ok(result)
+}
+
+#[inline(never)]
+fn matrix_multiply_2x2_any(a: &[f64], b: &[f64]) -> Result<[f64; 4]> {
+    let mut result = [0.0; 4];
+    checked::matrix_multiply_2x2(a, b, &mut result)?;
+    Ok(result)
+}
+
+fn main() -> ExitCode {
+    let mut numbers = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.parse::<i32>() {
+            Ok(n) => numbers.push(n),
+            Err(err) => {
+                eprintln!("`{arg}`: {err}\nusage: checked_ranges <n>...");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let values: Vec<f64> = numbers.iter().map(|&n| f64::from(n)).collect();
+    let (first, rest) = values.split_at(values.len() / 2);
+    let eight = |values: &[f64]| std::array::from_fn(|i| values.get(i).copied().unwrap_or(0.0));
+    let four = |values: &[f64]| std::array::from_fn(|i| values.get(i).copied().unwrap_or(0.0));
+
+    for &n in &numbers {
+        let small = n as i16;
+        println!("inc({small}) = {:?}", inc_in_range(small));
+        println!("inc({n}) = {:?}", inc_any(n));
+        println!("dec({small}) = {:?}", dec_in_range(small));
+        println!("dec({n}) = {:?}", dec_any(n));
+        println!(
+            "factorial({}) = {:?}",
+            n as u8 % 13,
+            factorial_in_range(n as u8)
+        );
+        println!("factorial({n}) = {:?}", factorial_any(n));
+        println!("{small} * {small} = {:?}", apply_op_in_range(small, small));
+        println!("{n} * {n} = {:?}", apply_op_any(n, n));
+    }
+    println!(
+        "dot = {:?}",
+        vector_dot_product_in_range(&eight(first), &eight(rest))
+    );
+    println!("dot = {:?}", vector_dot_product_any(first, rest));
+    println!(
+        "matrix = {:?}",
+        matrix_multiply_2x2_in_range(&four(first), &four(rest))
+    );
+    println!("matrix = {:?}", matrix_multiply_2x2_any(first, rest));
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,135 @@
+//! Prints per-function instruction counts of LLVM IR files.
+//!
+//! ```text
+//! cargo run --bin ir_report -- [--filter <text>] [--overflow] <file.ll|file.bc>...
+//! ```
+//!
+//! `--filter` keeps the functions whose demangled name contains `text`, e.g.
+//! `callback_variants` for the callback wrapper variants. `--overflow` keeps
+//! the integer kernels and their `checked_`, `wrapping_` and `saturating_`
+//! variants, grouped by kernel, with the `nsw`/`nuw` arithmetic and the
+//! overflow intrinsics of each.
+//!
+//! A function whose body folded down to returning a constant is listed with
+//! the constant, e.g. `buffer_bytes -> i32 2048`. `vbits` is the width of
+//! the widest vector a function loads or stores: 128 for SSE, 256 for AVX2.
+//! Bitcode is disassembled with the `llvm-dis` that matches rustc's LLVM.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::{env, fs};
+
+use ir_comparison::ir_analysis::{self, Function};
+use ir_comparison::toolchain::{Tool, Toolchain};
+
+fn main() -> ExitCode {
+    let mut filter = None;
+    let mut overflow = false;
+    let mut files = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--filter" => match args.next() {
+                Some(text) => filter = Some(text),
+                None => return usage("`--filter` needs a value"),
+            },
+            "--overflow" => overflow = true,
+            _ if arg.starts_with('-') => return usage(&format!("unknown option `{arg}`")),
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+    if files.is_empty() {
+        return usage("no IR files given");
+    }
+
+    for file in &files {
+        let ir = match read_ir(file) {
+            Ok(ir) => ir,
+            Err(err) => {
+                eprintln!("{}: {err}", file.display());
+                return ExitCode::FAILURE;
+            }
+        };
+        let functions: Vec<Function> = ir_analysis::functions(&ir)
+            .into_iter()
+            .filter(|function| {
+                filter
+                    .as_ref()
+                    .is_none_or(|text| function.name.contains(text))
+            })
+            .collect();
+        println!("{}", file.display());
+        if overflow {
+            print_overflow_variants(&functions);
+            continue;
+        }
+        println!(
+            "{:>8} {:>8} {:>8} {:>8} {:>8} {:>8}  function",
+            "instrs", "calls", "branches", "invokes", "lpads", "vbits"
+        );
+        for function in functions {
+            let stats = function.stats;
+            let constant = function
+                .constant
+                .map(|constant| format!(" -> {constant}"))
+                .unwrap_or_default();
+            println!(
+                "{:>8} {:>8} {:>8} {:>8} {:>8} {:>8}  {}{constant}",
+                stats.instructions,
+                stats.calls,
+                stats.branches,
+                stats.invokes,
+                stats.landing_pads,
+                stats.vector_bits,
+                function.name
+            );
+        }
+        println!();
+    }
+    ExitCode::SUCCESS
+}
+
+fn print_overflow_variants(functions: &[Function]) {
+    println!(
+        "{:>8} {:>8} {:>8} {:>8}  {:<10}  function",
+        "instrs", "calls", "nowrap", "ovf", "variant"
+    );
+    for variant in ir_analysis::overflow_variants(functions) {
+        let stats = variant.function.stats;
+        println!(
+            "{:>8} {:>8} {:>8} {:>8}  {:<10}  {}",
+            stats.instructions,
+            stats.calls,
+            stats.no_wrap,
+            stats.overflow_intrinsics,
+            variant.overflow.name(),
+            variant.function.name
+        );
+    }
+    println!();
+}
+
+/// Reads a `.ll` file, or disassembles anything else as bitcode.
+fn read_ir(file: &Path) -> Result<String, String> {
+    if file.extension().is_some_and(|ext| ext == "ll") {
+        return fs::read_to_string(file).map_err(|err| err.to_string());
+    }
+    let llvm_dis = Toolchain::from_env()
+        .and_then(|toolchain| toolchain.find(Tool::LlvmDis))
+        .map_err(|err| err.to_string())?;
+    let output = Command::new(&llvm_dis)
+        .arg(file)
+        .arg("-o")
+        .arg("-")
+        .output()
+        .map_err(|err| format!("couldn't run {}: {err}", llvm_dis.display()))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    String::from_utf8(output.stdout).map_err(|err| err.to_string())
+}
+
+fn usage(problem: &str) -> ExitCode {
+    eprintln!("{problem}\nusage: ir_report [--filter <text>] [--overflow] <file.ll|file.bc>...");
+    ExitCode::FAILURE
+}
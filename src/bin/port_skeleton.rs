@@ -0,0 +1,291 @@
+//! Writes a `native` skeleton for every C function that has no port yet.
+//!
+//! ```text
+//! cargo run --features port-skeleton --bin port_skeleton
+//! ```
+//!
+//! Each binding module `c_ffi::<module>` gets a port module in
+//! `src/rust_port/<module>.rs`, declared in `src/rust_port.rs`. Each C
+//! function missing from it is appended with a `todo!()` body and the
+//! signature of its safe wrapper: slices instead of pointer and length,
+//! `bool` instead of `int` flags, and Rust types instead of C type aliases.
+//! A function without a safe wrapper keeps the raw pointer signature of its
+//! binding. Functions already in the port are never touched. A header
+//! generated in extra enum styles is ported once, after its first module.
+
+use std::any::type_name;
+use std::collections::BTreeSet;
+use std::fs;
+use std::os::raw;
+use std::path::Path;
+
+use quote::{format_ident, quote};
+use syn::visit_mut::VisitMut;
+use syn::{ForeignItem, ForeignItemFn, Item, ItemFn, Signature, Type};
+
+#[cfg(not(feature = "pregenerated-bindings"))]
+const BINDINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(feature = "pregenerated-bindings")]
+const BINDINGS: &str = include_str!("../bindings_pregenerated.rs");
+
+fn main() {
+    write_skeletons(
+        BINDINGS,
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &Path::new(env!("OUT_DIR")).join("safe_wrappers"),
+    );
+}
+
+/// Writes the skeletons for `bindings` under `src`, with the signatures of
+/// the safe wrappers in `wrapper_dir`.
+pub fn write_skeletons(bindings: &str, src: &Path, wrapper_dir: &Path) {
+    let port_root = src.join("rust_port.rs");
+    let bindings = syn::parse_file(bindings).expect("couldn't parse the bindings");
+    let mut headers = BTreeSet::new();
+    for item in bindings.items {
+        let Item::Mod(module) = item else { continue };
+        let name = module.ident.to_string();
+        let header = doc_comment(&module.attrs)
+            .split('`')
+            .nth(1)
+            .unwrap_or_default()
+            .to_owned();
+        if !headers.insert(header.clone()) {
+            continue;
+        }
+        let items = module.content.map(|(_, items)| items).unwrap_or_default();
+        let defined = defined_types(&items);
+        let functions: Vec<ForeignItemFn> = items
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::ForeignMod(foreign) => Some(foreign.items),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|item| match item {
+                ForeignItem::Fn(func) => Some(func),
+                _ => None,
+            })
+            .collect();
+
+        let path = src.join("rust_port").join(format!("{name}.rs"));
+        let (mut text, ported) = if path.exists() {
+            let text = read(&path);
+            let ported = syn::parse_file(&text)
+                .unwrap_or_else(|err| panic!("couldn't parse {}: {err}", path.display()))
+                .items
+                .into_iter()
+                .filter_map(|item| match item {
+                    Item::Fn(func) => Some(func.sig.ident.to_string()),
+                    _ => None,
+                })
+                .collect();
+            (text, ported)
+        } else {
+            (format!("//! Port of `{header}`.\n"), BTreeSet::new())
+        };
+
+        let safe = safe_wrappers(wrapper_dir, &name);
+        let mut added = Vec::new();
+        for func in functions {
+            let fn_name = func.sig.ident.to_string();
+            if ported.contains(&fn_name) {
+                continue;
+            }
+            let sig = match safe
+                .iter()
+                .find(|f| f.sig.ident == format!("{fn_name}_safe"))
+            {
+                Some(wrapper) => wrapper.sig.clone(),
+                None => {
+                    println!(
+                        "{name}::{fn_name}: no safe wrapper, so the port takes raw pointers; \
+                         annotate them in `{header}` and rerun"
+                    );
+                    func.sig.clone()
+                }
+            };
+            text.push('\n');
+            let doc = doc_comment(&func.attrs);
+            text.push_str(&skeleton(&name, &defined, &fn_name, sig, &doc));
+            added.push(fn_name);
+        }
+        if added.is_empty() {
+            continue;
+        }
+
+        fs::create_dir_all(path.parent().unwrap()).expect("couldn't create src/rust_port");
+        fs::write(&path, text).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        declare_module(&port_root, &name);
+        println!("{}: added {}", path.display(), added.join(", "));
+    }
+}
+
+/// Renders a `todo!()` port of `fn_name` with the given signature. `defined`
+/// names the types of binding module `module`.
+fn skeleton(
+    module: &str,
+    defined: &BTreeSet<String>,
+    fn_name: &str,
+    mut sig: Signature,
+    doc: &str,
+) -> String {
+    sig.ident = format_ident!("{fn_name}");
+    sig.unsafety = None;
+    sig.abi = None;
+    let mut types = RustTypes { module, defined };
+    for arg in sig.inputs.iter_mut() {
+        if let syn::FnArg::Typed(arg) = arg {
+            types.visit_type_mut(&mut arg.ty);
+        }
+    }
+    if let syn::ReturnType::Type(_, ty) = &mut sig.output {
+        types.visit_type_mut(ty);
+    }
+
+    let mut docs: Vec<String> = doc
+        .lines()
+        .filter(|line| !line.trim().starts_with("@ir"))
+        .map(str::to_owned)
+        .collect();
+    while docs.last().is_some_and(|line| line.trim().is_empty()) {
+        docs.pop();
+    }
+    let func: ItemFn = syn::parse2(quote! {
+        #(#[doc = #docs])*
+        #[allow(unused_variables)]
+        pub #sig {
+            todo!()
+        }
+    })
+    .expect("couldn't build a skeleton");
+    prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![Item::Fn(func)],
+    })
+}
+
+/// Adds `pub mod <module>;` to `rust_port.rs` if it isn't declared yet.
+fn declare_module(port_root: &Path, module: &str) {
+    let text = read(port_root);
+    let declared = syn::parse_file(&text)
+        .unwrap_or_else(|err| panic!("couldn't parse {}: {err}", port_root.display()))
+        .items
+        .iter()
+        .any(|item| matches!(item, Item::Mod(m) if m.ident == module));
+    if declared {
+        return;
+    }
+    let mut lines: Vec<&str> = text.lines().collect();
+    let declaration = format!("pub mod {module};");
+    let at = lines
+        .iter()
+        .rposition(|line| line.starts_with("pub mod "))
+        .map_or(lines.len(), |last| last + 1);
+    lines.insert(at, &declaration);
+    fs::write(port_root, lines.join("\n") + "\n")
+        .unwrap_or_else(|err| panic!("{}: {err}", port_root.display()));
+}
+
+/// The names a binding module defines types under: its structs, unions,
+/// enums, type aliases and the modules of moduleified enums.
+fn defined_types(items: &[Item]) -> BTreeSet<String> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(item) => Some(&item.ident),
+            Item::Union(item) => Some(&item.ident),
+            Item::Enum(item) => Some(&item.ident),
+            Item::Type(item) => Some(&item.ident),
+            Item::Mod(item) => Some(&item.ident),
+            _ => None,
+        })
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// The generated safe wrappers of a binding module.
+fn safe_wrappers(wrapper_dir: &Path, module: &str) -> Vec<ItemFn> {
+    let path = wrapper_dir.join(format!("{module}.rs"));
+    syn::parse_file(&read(&path))
+        .unwrap_or_else(|err| panic!("couldn't parse {}: {err}", path.display()))
+        .items
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::Fn(func) => Some(func),
+            _ => None,
+        })
+        .collect()
+}
+
+fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    let mut doc = String::new();
+    for attr in attrs {
+        if let syn::Meta::NameValue(meta) = &attr.meta
+            && meta.path.is_ident("doc")
+            && let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(text),
+                ..
+            }) = &meta.value
+        {
+            doc.push_str(&text.value());
+            doc.push('\n');
+        }
+    }
+    doc
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|err| panic!("couldn't read {}: {err}", path.display()))
+}
+
+/// Replaces C type aliases with the Rust type they stand for, and names the
+/// types bindgen generated through `c_ffi`. Other types, such as the
+/// `Option` of a checked wrapper, are left as they are.
+struct RustTypes<'a> {
+    module: &'a str,
+    defined: &'a BTreeSet<String>,
+}
+
+impl VisitMut for RustTypes<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        syn::visit_mut::visit_type_mut(self, ty);
+        let Type::Path(path) = ty else { return };
+        if path.qself.is_some() {
+            return;
+        }
+        let last = path.path.segments.last().unwrap().ident.to_string();
+        if let Some(alias) = c_alias(&last) {
+            *ty = syn::parse_str(alias).unwrap();
+        } else if path.path.leading_colon.is_none()
+            && self
+                .defined
+                .contains(&path.path.segments[0].ident.to_string())
+        {
+            let module = format_ident!("{}", self.module);
+            let inner = &path.path;
+            *ty = syn::parse2(quote!(crate::c_ffi::#module::#inner)).unwrap();
+        }
+    }
+}
+
+/// The Rust type a C type alias stands for on this target.
+fn c_alias(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "c_char" => type_name::<raw::c_char>(),
+        "c_schar" => type_name::<raw::c_schar>(),
+        "c_uchar" => type_name::<raw::c_uchar>(),
+        "c_short" => type_name::<raw::c_short>(),
+        "c_ushort" => type_name::<raw::c_ushort>(),
+        "c_int" => type_name::<raw::c_int>(),
+        "c_uint" => type_name::<raw::c_uint>(),
+        "c_long" => type_name::<raw::c_long>(),
+        "c_ulong" => type_name::<raw::c_ulong>(),
+        "c_longlong" => type_name::<raw::c_longlong>(),
+        "c_ulonglong" => type_name::<raw::c_ulonglong>(),
+        "c_float" => type_name::<raw::c_float>(),
+        "c_double" => type_name::<raw::c_double>(),
+        _ => return None,
+    })
+}
@@ -0,0 +1,51 @@
+//! The bitfield and union kernels written in Rust over the types bindgen
+//! generated, rather than over the types of the port. The bitfields go
+//! through bindgen's accessor methods and the unions through `unsafe` field
+//! reads, so their IR sits between the C functions and the port in `native`.
+//!
+//! Compare the three with `ir_report --filter instr_` and
+//! `ir_report --filter value_`.
+
+use crate::c_ffi::bitfields::Instr;
+use crate::c_ffi::unions::{self, Value};
+
+pub fn instr_encode(opcode: u32, dest: u32, src: u32, imm: u32) -> Instr {
+    Instr {
+        _bitfield_align_1: [],
+        _bitfield_1: Instr::new_bitfield_1(opcode, dest, src, imm),
+    }
+}
+
+pub fn instr_opcode(instr: Instr) -> u32 {
+    instr.opcode()
+}
+
+pub fn instr_imm(instr: Instr) -> u32 {
+    instr.imm()
+}
+
+pub fn instr_set_dest(instr: &mut Instr, dest: u32) {
+    instr.set_dest(dest)
+}
+
+pub fn instr_sum_imm(instrs: &[Instr]) -> u32 {
+    instrs
+        .iter()
+        .fold(0, |sum: u32, instr| sum.wrapping_add(instr.imm()))
+}
+
+pub fn value_to_double(v: Value) -> f64 {
+    // SAFETY: `kind` says which member of `data` was written.
+    unsafe {
+        match v.kind {
+            unions::ValueKind_VALUE_INT => v.data.i as f64,
+            unions::ValueKind_VALUE_FLOAT => v.data.f,
+            unions::ValueKind_VALUE_PAIR => f64::from(v.data.pair.a) + f64::from(v.data.pair.b),
+            _ => 0.0,
+        }
+    }
+}
+
+pub fn value_sum(values: &[Value]) -> f64 {
+    values.iter().map(|&v| value_to_double(v)).sum()
+}
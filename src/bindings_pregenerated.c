@@ -0,0 +1,19 @@
+// Generated by build.rs from the headers under c_src. Do not edit.
+
+// Static wrappers
+
+int inc__input_extern(int x) { return inc(x); }
+int dec__input_extern(int x) { return dec(x); }
+bool checked_inc__input_extern(int x, int *result) { return checked_inc(x, result); }
+int wrapping_inc__input_extern(int x) { return wrapping_inc(x); }
+int saturating_inc__input_extern(int x) { return saturating_inc(x); }
+bool checked_dec__input_extern(int x, int *result) { return checked_dec(x, result); }
+int wrapping_dec__input_extern(int x) { return wrapping_dec(x); }
+int saturating_dec__input_extern(int x) { return saturating_dec(x); }
+// Static wrappers
+
+int clamp_int__macros_extern(int x, int lo, int hi) { return clamp_int(x, lo, hi); }
+int in_range__macros_extern(int x, int lo, int hi) { return in_range(x, lo, hi); }
+int clamp__macros_extern(int x, int lo, int hi) { return clamp(x, lo, hi); }
+int clamp_temp__macros_extern(int t) { return clamp_temp(t); }
+size_t buffer_offset__macros_extern(size_t i) { return buffer_offset(i); }
@@ -0,0 +1,1082 @@
+// Generated by build.rs from the headers under c_src. Do not edit.
+
+/// Bindings for `c_src/bitfields.h`.
+pub mod bitfields {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct __BindgenBitfieldUnit<Storage> {
+    storage: Storage,
+}
+impl<Storage> __BindgenBitfieldUnit<Storage> {
+    #[inline]
+    pub const fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+}
+impl<Storage> __BindgenBitfieldUnit<Storage>
+where
+    Storage: AsRef<[u8]> + AsMut<[u8]>,
+{
+    #[inline]
+    fn extract_bit(byte: u8, index: usize) -> bool {
+        let bit_index = if cfg!(target_endian = "big") {
+            7 - (index % 8)
+        } else {
+            index % 8
+        };
+        let mask = 1 << bit_index;
+        byte & mask == mask
+    }
+    #[inline]
+    pub fn get_bit(&self, index: usize) -> bool {
+        debug_assert!(index / 8 < self.storage.as_ref().len());
+        let byte_index = index / 8;
+        let byte = self.storage.as_ref()[byte_index];
+        Self::extract_bit(byte, index)
+    }
+    #[inline]
+    pub unsafe fn raw_get_bit(this: *const Self, index: usize) -> bool {
+        debug_assert!(index / 8 < core::mem::size_of::<Storage>());
+        let byte_index = index / 8;
+        let byte = unsafe {
+            *(core::ptr::addr_of!((*this).storage) as *const u8).offset(byte_index as isize)
+        };
+        Self::extract_bit(byte, index)
+    }
+    #[inline]
+    fn change_bit(byte: u8, index: usize, val: bool) -> u8 {
+        let bit_index = if cfg!(target_endian = "big") {
+            7 - (index % 8)
+        } else {
+            index % 8
+        };
+        let mask = 1 << bit_index;
+        if val { byte | mask } else { byte & !mask }
+    }
+    #[inline]
+    pub fn set_bit(&mut self, index: usize, val: bool) {
+        debug_assert!(index / 8 < self.storage.as_ref().len());
+        let byte_index = index / 8;
+        let byte = &mut self.storage.as_mut()[byte_index];
+        *byte = Self::change_bit(*byte, index, val);
+    }
+    #[inline]
+    pub unsafe fn raw_set_bit(this: *mut Self, index: usize, val: bool) {
+        debug_assert!(index / 8 < core::mem::size_of::<Storage>());
+        let byte_index = index / 8;
+        let byte = unsafe {
+            (core::ptr::addr_of_mut!((*this).storage) as *mut u8).offset(byte_index as isize)
+        };
+        unsafe { *byte = Self::change_bit(*byte, index, val) };
+    }
+    #[inline]
+    pub fn get(&self, bit_offset: usize, bit_width: u8) -> u64 {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < self.storage.as_ref().len());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= self.storage.as_ref().len());
+        let mut val = 0;
+        for i in 0..(bit_width as usize) {
+            if self.get_bit(i + bit_offset) {
+                let index = if cfg!(target_endian = "big") {
+                    bit_width as usize - 1 - i
+                } else {
+                    i
+                };
+                val |= 1 << index;
+            }
+        }
+        val
+    }
+    #[inline]
+    pub unsafe fn raw_get(this: *const Self, bit_offset: usize, bit_width: u8) -> u64 {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < core::mem::size_of::<Storage>());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= core::mem::size_of::<Storage>());
+        let mut val = 0;
+        for i in 0..(bit_width as usize) {
+            if unsafe { Self::raw_get_bit(this, i + bit_offset) } {
+                let index = if cfg!(target_endian = "big") {
+                    bit_width as usize - 1 - i
+                } else {
+                    i
+                };
+                val |= 1 << index;
+            }
+        }
+        val
+    }
+    #[inline]
+    pub fn set(&mut self, bit_offset: usize, bit_width: u8, val: u64) {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < self.storage.as_ref().len());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= self.storage.as_ref().len());
+        for i in 0..(bit_width as usize) {
+            let mask = 1 << i;
+            let val_bit_is_set = val & mask == mask;
+            let index = if cfg!(target_endian = "big") {
+                bit_width as usize - 1 - i
+            } else {
+                i
+            };
+            self.set_bit(index + bit_offset, val_bit_is_set);
+        }
+    }
+    #[inline]
+    pub unsafe fn raw_set(this: *mut Self, bit_offset: usize, bit_width: u8, val: u64) {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < core::mem::size_of::<Storage>());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= core::mem::size_of::<Storage>());
+        for i in 0..(bit_width as usize) {
+            let mask = 1 << i;
+            let val_bit_is_set = val & mask == mask;
+            let index = if cfg!(target_endian = "big") {
+                bit_width as usize - 1 - i
+            } else {
+                i
+            };
+            unsafe { Self::raw_set_bit(this, index + bit_offset, val_bit_is_set) };
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Instr {
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of Instr"][::std::mem::size_of::<Instr>() - 4usize];
+    ["Alignment of Instr"][::std::mem::align_of::<Instr>() - 4usize];
+};
+impl Instr {
+    #[inline]
+    pub fn opcode(&self) -> ::std::os::raw::c_uint {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 6u8) as u32) }
+    }
+    #[inline]
+    pub fn set_opcode(&mut self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 6u8, val as u64)
+        }
+    }
+    #[inline]
+    pub unsafe fn opcode_raw(this: *const Self) -> ::std::os::raw::c_uint {
+        unsafe {
+            ::std::mem::transmute(<__BindgenBitfieldUnit<[u8; 4usize]>>::raw_get(
+                ::std::ptr::addr_of!((*this)._bitfield_1),
+                0usize,
+                6u8,
+            ) as u32)
+        }
+    }
+    #[inline]
+    pub unsafe fn set_opcode_raw(this: *mut Self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            <__BindgenBitfieldUnit<[u8; 4usize]>>::raw_set(
+                ::std::ptr::addr_of_mut!((*this)._bitfield_1),
+                0usize,
+                6u8,
+                val as u64,
+            )
+        }
+    }
+    #[inline]
+    pub fn dest(&self) -> ::std::os::raw::c_uint {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(6usize, 5u8) as u32) }
+    }
+    #[inline]
+    pub fn set_dest(&mut self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(6usize, 5u8, val as u64)
+        }
+    }
+    #[inline]
+    pub unsafe fn dest_raw(this: *const Self) -> ::std::os::raw::c_uint {
+        unsafe {
+            ::std::mem::transmute(<__BindgenBitfieldUnit<[u8; 4usize]>>::raw_get(
+                ::std::ptr::addr_of!((*this)._bitfield_1),
+                6usize,
+                5u8,
+            ) as u32)
+        }
+    }
+    #[inline]
+    pub unsafe fn set_dest_raw(this: *mut Self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            <__BindgenBitfieldUnit<[u8; 4usize]>>::raw_set(
+                ::std::ptr::addr_of_mut!((*this)._bitfield_1),
+                6usize,
+                5u8,
+                val as u64,
+            )
+        }
+    }
+    #[inline]
+    pub fn src(&self) -> ::std::os::raw::c_uint {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 5u8) as u32) }
+    }
+    #[inline]
+    pub fn set_src(&mut self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 5u8, val as u64)
+        }
+    }
+    #[inline]
+    pub unsafe fn src_raw(this: *const Self) -> ::std::os::raw::c_uint {
+        unsafe {
+            ::std::mem::transmute(<__BindgenBitfieldUnit<[u8; 4usize]>>::raw_get(
+                ::std::ptr::addr_of!((*this)._bitfield_1),
+                11usize,
+                5u8,
+            ) as u32)
+        }
+    }
+    #[inline]
+    pub unsafe fn set_src_raw(this: *mut Self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            <__BindgenBitfieldUnit<[u8; 4usize]>>::raw_set(
+                ::std::ptr::addr_of_mut!((*this)._bitfield_1),
+                11usize,
+                5u8,
+                val as u64,
+            )
+        }
+    }
+    #[inline]
+    pub fn imm(&self) -> ::std::os::raw::c_uint {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(16usize, 16u8) as u32) }
+    }
+    #[inline]
+    pub fn set_imm(&mut self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(16usize, 16u8, val as u64)
+        }
+    }
+    #[inline]
+    pub unsafe fn imm_raw(this: *const Self) -> ::std::os::raw::c_uint {
+        unsafe {
+            ::std::mem::transmute(<__BindgenBitfieldUnit<[u8; 4usize]>>::raw_get(
+                ::std::ptr::addr_of!((*this)._bitfield_1),
+                16usize,
+                16u8,
+            ) as u32)
+        }
+    }
+    #[inline]
+    pub unsafe fn set_imm_raw(this: *mut Self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            <__BindgenBitfieldUnit<[u8; 4usize]>>::raw_set(
+                ::std::ptr::addr_of_mut!((*this)._bitfield_1),
+                16usize,
+                16u8,
+                val as u64,
+            )
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(
+        opcode: ::std::os::raw::c_uint,
+        dest: ::std::os::raw::c_uint,
+        src: ::std::os::raw::c_uint,
+        imm: ::std::os::raw::c_uint,
+    ) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 6u8, {
+            let opcode: u32 = unsafe { ::std::mem::transmute(opcode) };
+            opcode as u64
+        });
+        __bindgen_bitfield_unit.set(6usize, 5u8, {
+            let dest: u32 = unsafe { ::std::mem::transmute(dest) };
+            dest as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 5u8, {
+            let src: u32 = unsafe { ::std::mem::transmute(src) };
+            src as u64
+        });
+        __bindgen_bitfield_unit.set(16usize, 16u8, {
+            let imm: u32 = unsafe { ::std::mem::transmute(imm) };
+            imm as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+unsafe extern "C" {
+    pub fn instr_encode(
+        opcode: ::std::os::raw::c_uint,
+        dest: ::std::os::raw::c_uint,
+        src: ::std::os::raw::c_uint,
+        imm: ::std::os::raw::c_uint,
+    ) -> Instr;
+}
+unsafe extern "C" {
+    pub fn instr_opcode(instr: Instr) -> ::std::os::raw::c_uint;
+}
+unsafe extern "C" {
+    pub fn instr_imm(instr: Instr) -> ::std::os::raw::c_uint;
+}
+unsafe extern "C" {
+    #[doc = " @ir ref(instr)"]
+    pub fn instr_set_dest(instr: *mut Instr, dest: ::std::os::raw::c_uint);
+}
+unsafe extern "C" {
+    #[doc = " Sums the `imm` fields of `instrs`, wrapping around.\n\n @ir slice(instrs, len)"]
+    pub fn instr_sum_imm(instrs: *const Instr, len: usize) -> u32;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/bitfields.rs"));
+}
+
+/// Bindings for `c_src/callbacks.h`.
+pub mod callbacks {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+unsafe extern "C" {
+    #[doc = " Sorts `values` in place, in the order `compare` gives: negative, zero or\n positive, as with `qsort`.\n\n @ir slice(values, len)\n @ir callback(compare, context)\n @ir unwind(compare)"]
+    pub fn sort_ints(
+        values: *mut ::std::os::raw::c_int,
+        len: usize,
+        compare: ::std::option::Option<
+            unsafe extern "C" fn(
+                a: *const ::std::os::raw::c_int,
+                b: *const ::std::os::raw::c_int,
+                context: *mut ::std::os::raw::c_void,
+            ) -> ::std::os::raw::c_int,
+        >,
+        context: *mut ::std::os::raw::c_void,
+    );
+}
+unsafe extern "C" {
+    #[doc = " Replaces each of `values` with `f` applied to it.\n\n @ir slice(values, len)\n @ir callback(f, context)\n @ir unwind(f)"]
+    pub fn map_array(
+        values: *mut ::std::os::raw::c_int,
+        len: usize,
+        f: ::std::option::Option<
+            unsafe extern "C" fn(
+                x: ::std::os::raw::c_int,
+                context: *mut ::std::os::raw::c_void,
+            ) -> ::std::os::raw::c_int,
+        >,
+        context: *mut ::std::os::raw::c_void,
+    );
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/callbacks.rs"));
+}
+
+/// Bindings for `c_src/enums.h`.
+pub mod enums {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+pub const Shape_SHAPE_CIRCLE: Shape = 0;
+pub const Shape_SHAPE_SQUARE: Shape = 1;
+pub const Shape_SHAPE_TRIANGLE: Shape = 2;
+pub const Shape_SHAPE_HEXAGON: Shape = 3;
+pub type Shape = ::std::os::raw::c_uint;
+pub const Op_OP_ADD: Op = 1;
+pub const Op_OP_SUB: Op = 2;
+pub const Op_OP_MUL: Op = 4;
+pub const Op_OP_DIV: Op = 8;
+pub const Op_OP_NEG: Op = 16;
+pub type Op = ::std::os::raw::c_uint;
+unsafe extern "C" {
+    pub fn shape_sides(shape: Shape) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn shape_area(shape: Shape, size: f64) -> f64;
+}
+unsafe extern "C" {
+    pub fn shape_next(shape: Shape) -> Shape;
+}
+unsafe extern "C" {
+    pub fn apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Returns whether the operation overflows.\n\n @ir checked(result)"]
+    pub fn checked_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+        result: *mut ::std::os::raw::c_int,
+    ) -> bool;
+}
+unsafe extern "C" {
+    pub fn wrapping_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn saturating_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/enums.rs"));
+}
+
+/// Bindings for `c_src/enums.h`, with `rust` enums.
+#[allow(clashing_extern_declarations)]
+pub mod enums_rust {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum Shape {
+    SHAPE_CIRCLE = 0,
+    SHAPE_SQUARE = 1,
+    SHAPE_TRIANGLE = 2,
+    SHAPE_HEXAGON = 3,
+}
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum Op {
+    OP_ADD = 1,
+    OP_SUB = 2,
+    OP_MUL = 4,
+    OP_DIV = 8,
+    OP_NEG = 16,
+}
+unsafe extern "C" {
+    pub fn shape_sides(shape: Shape) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn shape_area(shape: Shape, size: f64) -> f64;
+}
+unsafe extern "C" {
+    pub fn shape_next(shape: Shape) -> Shape;
+}
+unsafe extern "C" {
+    pub fn apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Returns whether the operation overflows.\n\n @ir checked(result)"]
+    pub fn checked_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+        result: *mut ::std::os::raw::c_int,
+    ) -> bool;
+}
+unsafe extern "C" {
+    pub fn wrapping_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn saturating_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/enums_rust.rs"));
+}
+
+/// Bindings for `c_src/enums.h`, with `rust_non_exhaustive` enums.
+#[allow(clashing_extern_declarations)]
+pub mod enums_rust_non_exhaustive {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum Shape {
+    SHAPE_CIRCLE = 0,
+    SHAPE_SQUARE = 1,
+    SHAPE_TRIANGLE = 2,
+    SHAPE_HEXAGON = 3,
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum Op {
+    OP_ADD = 1,
+    OP_SUB = 2,
+    OP_MUL = 4,
+    OP_DIV = 8,
+    OP_NEG = 16,
+}
+unsafe extern "C" {
+    pub fn shape_sides(shape: Shape) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn shape_area(shape: Shape, size: f64) -> f64;
+}
+unsafe extern "C" {
+    pub fn shape_next(shape: Shape) -> Shape;
+}
+unsafe extern "C" {
+    pub fn apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Returns whether the operation overflows.\n\n @ir checked(result)"]
+    pub fn checked_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+        result: *mut ::std::os::raw::c_int,
+    ) -> bool;
+}
+unsafe extern "C" {
+    pub fn wrapping_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn saturating_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/enums_rust_non_exhaustive.rs"));
+}
+
+/// Bindings for `c_src/enums.h`, with `moduleconsts` enums.
+#[allow(clashing_extern_declarations)]
+pub mod enums_moduleconsts {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+pub mod Shape {
+    pub type Type = ::std::os::raw::c_uint;
+    pub const SHAPE_CIRCLE: Type = 0;
+    pub const SHAPE_SQUARE: Type = 1;
+    pub const SHAPE_TRIANGLE: Type = 2;
+    pub const SHAPE_HEXAGON: Type = 3;
+}
+pub mod Op {
+    pub type Type = ::std::os::raw::c_uint;
+    pub const OP_ADD: Type = 1;
+    pub const OP_SUB: Type = 2;
+    pub const OP_MUL: Type = 4;
+    pub const OP_DIV: Type = 8;
+    pub const OP_NEG: Type = 16;
+}
+unsafe extern "C" {
+    pub fn shape_sides(shape: Shape::Type) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn shape_area(shape: Shape::Type, size: f64) -> f64;
+}
+unsafe extern "C" {
+    pub fn shape_next(shape: Shape::Type) -> Shape::Type;
+}
+unsafe extern "C" {
+    pub fn apply_op(
+        op: Op::Type,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Returns whether the operation overflows.\n\n @ir checked(result)"]
+    pub fn checked_apply_op(
+        op: Op::Type,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+        result: *mut ::std::os::raw::c_int,
+    ) -> bool;
+}
+unsafe extern "C" {
+    pub fn wrapping_apply_op(
+        op: Op::Type,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn saturating_apply_op(
+        op: Op::Type,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/enums_moduleconsts.rs"));
+}
+
+/// Bindings for `c_src/enums.h`, with `newtype` enums.
+#[allow(clashing_extern_declarations)]
+pub mod enums_newtype {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+impl Shape {
+    pub const SHAPE_CIRCLE: Shape = Shape(0);
+}
+impl Shape {
+    pub const SHAPE_SQUARE: Shape = Shape(1);
+}
+impl Shape {
+    pub const SHAPE_TRIANGLE: Shape = Shape(2);
+}
+impl Shape {
+    pub const SHAPE_HEXAGON: Shape = Shape(3);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Shape(pub ::std::os::raw::c_uint);
+impl Op {
+    pub const OP_ADD: Op = Op(1);
+}
+impl Op {
+    pub const OP_SUB: Op = Op(2);
+}
+impl Op {
+    pub const OP_MUL: Op = Op(4);
+}
+impl Op {
+    pub const OP_DIV: Op = Op(8);
+}
+impl Op {
+    pub const OP_NEG: Op = Op(16);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Op(pub ::std::os::raw::c_uint);
+unsafe extern "C" {
+    pub fn shape_sides(shape: Shape) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn shape_area(shape: Shape, size: f64) -> f64;
+}
+unsafe extern "C" {
+    pub fn shape_next(shape: Shape) -> Shape;
+}
+unsafe extern "C" {
+    pub fn apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Returns whether the operation overflows.\n\n @ir checked(result)"]
+    pub fn checked_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+        result: *mut ::std::os::raw::c_int,
+    ) -> bool;
+}
+unsafe extern "C" {
+    pub fn wrapping_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn saturating_apply_op(
+        op: Op,
+        a: ::std::os::raw::c_int,
+        b: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/enums_newtype.rs"));
+}
+
+/// Bindings for `c_src/flex.h`.
+pub mod flex {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+#[repr(C)]
+#[derive(Default)]
+pub struct __IncompleteArrayField<T>(::std::marker::PhantomData<T>, [T; 0]);
+impl<T> __IncompleteArrayField<T> {
+    #[inline]
+    pub const fn new() -> Self {
+        __IncompleteArrayField(::std::marker::PhantomData, [])
+    }
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self as *const _ as *const T
+    }
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self as *mut _ as *mut T
+    }
+    #[inline]
+    pub unsafe fn as_slice(&self, len: usize) -> &[T] {
+        unsafe { ::std::slice::from_raw_parts(self.as_ptr(), len) }
+    }
+    #[inline]
+    pub unsafe fn as_mut_slice(&mut self, len: usize) -> &mut [T] {
+        unsafe { ::std::slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+    }
+}
+impl<T> ::std::fmt::Debug for __IncompleteArrayField<T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        fmt.write_str("__IncompleteArrayField")
+    }
+}
+#[repr(C)]
+#[derive(Debug)]
+pub struct Series {
+    pub len: usize,
+    pub data: __IncompleteArrayField<f64>,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of Series"][::std::mem::size_of::<Series>() - 8usize];
+    ["Alignment of Series"][::std::mem::align_of::<Series>() - 8usize];
+    ["Offset of field: Series::len"][::std::mem::offset_of!(Series, len) - 0usize];
+    ["Offset of field: Series::data"][::std::mem::offset_of!(Series, data) - 8usize];
+};
+unsafe extern "C" {
+    pub fn series_sum(s: *const Series) -> f64;
+}
+unsafe extern "C" {
+    pub fn series_scale(s: *mut Series, k: f64);
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/flex.rs"));
+}
+
+/// Bindings for `c_src/input.h`.
+pub mod input {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+unsafe extern "C" {
+    #[link_name = "inc__input_extern"]
+    pub fn inc(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[link_name = "dec__input_extern"]
+    pub fn dec(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Returns whether `x + 1` overflows.\n\n @ir checked(result)"]
+    #[link_name = "checked_inc__input_extern"]
+    pub fn checked_inc(x: ::std::os::raw::c_int, result: *mut ::std::os::raw::c_int) -> bool;
+}
+unsafe extern "C" {
+    #[link_name = "wrapping_inc__input_extern"]
+    pub fn wrapping_inc(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[link_name = "saturating_inc__input_extern"]
+    pub fn saturating_inc(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Returns whether `x - 1` overflows.\n\n @ir checked(result)"]
+    #[link_name = "checked_dec__input_extern"]
+    pub fn checked_dec(x: ::std::os::raw::c_int, result: *mut ::std::os::raw::c_int) -> bool;
+}
+unsafe extern "C" {
+    #[link_name = "wrapping_dec__input_extern"]
+    pub fn wrapping_dec(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[link_name = "saturating_dec__input_extern"]
+    pub fn saturating_dec(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/input.rs"));
+}
+
+/// Bindings for `c_src/macros.h`.
+pub mod macros {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+pub type Color = ::std::os::raw::c_int;
+
+pub const BUFFER_LEN: i32 = 256;
+pub const ELEM_SIZE: i32 = 8;
+pub const BUFFER_BYTES: i32 = 2048;
+pub const FLAG_MASK: i32 = 128;
+pub const SCALE: f64 = 0.125;
+pub const MIN_TEMP: i32 = -40;
+pub const MAX_TEMP: i32 = 60;
+pub const COLOR_RED: Color = 0;
+pub const COLOR_GREEN: Color = 1;
+pub const COLOR_BLUE: Color = 2;
+unsafe extern "C" {
+    #[link_name = "clamp_int__macros_extern"]
+    pub fn clamp_int(
+        x: ::std::os::raw::c_int,
+        lo: ::std::os::raw::c_int,
+        hi: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[link_name = "in_range__macros_extern"]
+    pub fn in_range(
+        x: ::std::os::raw::c_int,
+        lo: ::std::os::raw::c_int,
+        hi: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn buffer_bytes() -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn scaled_buffer_bytes() -> f64;
+}
+unsafe extern "C" {
+    pub fn set_flag(flags: ::std::os::raw::c_uint) -> ::std::os::raw::c_uint;
+}
+unsafe extern "C" {
+    pub fn next_color(color: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[link_name = "clamp__macros_extern"]
+    pub fn clamp(
+        x: ::std::os::raw::c_int,
+        lo: ::std::os::raw::c_int,
+        hi: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[link_name = "clamp_temp__macros_extern"]
+    pub fn clamp_temp(t: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " The byte offset of element `i` in the ring buffer."]
+    #[link_name = "buffer_offset__macros_extern"]
+    pub fn buffer_offset(i: usize) -> usize;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/macros.rs"));
+}
+
+/// Bindings for `c_src/math_ops.h`.
+pub mod math_ops {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+unsafe extern "C" {
+    pub fn factorial(n: i32) -> i32;
+}
+unsafe extern "C" {
+    #[doc = " @ir slice(a, b, len)"]
+    pub fn vector_dot_product(a: *const f64, b: *const f64, len: usize) -> f64;
+}
+unsafe extern "C" {
+    pub fn is_prime(n: u32) -> bool;
+}
+unsafe extern "C" {
+    #[doc = " @ir array(a, 4)\n @ir array(b, 4)\n @ir out(result, 4)"]
+    pub fn matrix_multiply_2x2(a: *const f64, b: *const f64, result: *mut f64);
+}
+unsafe extern "C" {
+    #[doc = " Returns whether `n!` overflows.\n\n @ir checked(result)"]
+    pub fn checked_factorial(n: i32, result: *mut i32) -> bool;
+}
+unsafe extern "C" {
+    pub fn wrapping_factorial(n: i32) -> i32;
+}
+unsafe extern "C" {
+    pub fn saturating_factorial(n: i32) -> i32;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/math_ops.rs"));
+}
+
+/// Bindings for `c_src/simd.h`.
+pub mod simd {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+unsafe extern "C" {
+    #[doc = " `y = a * x + y`, 4 elements at a time.\n\n @ir slice(x, y, n)"]
+    pub fn saxpy_sse2(a: f32, x: *const f32, y: *mut f32, n: usize);
+}
+unsafe extern "C" {
+    #[doc = " `y = a * x + y`, 8 elements at a time.\n\n @ir slice(x, y, n)\n @ir target_feature(avx2)"]
+    pub fn saxpy_avx2(a: f32, x: *const f32, y: *mut f32, n: usize);
+}
+unsafe extern "C" {
+    #[doc = " The sum of `x`, over 4 lanes that are added up at the end:\n `(l0 + l1) + (l2 + l3)`, then the rest of `x` in order.\n\n @ir slice(x, n)"]
+    pub fn sum_sse2(x: *const f32, n: usize) -> f32;
+}
+unsafe extern "C" {
+    #[doc = " The sum of `x`, over 8 lanes that are added up pairwise at the end.\n\n @ir slice(x, n)\n @ir target_feature(avx2)"]
+    pub fn sum_avx2(x: *const f32, n: usize) -> f32;
+}
+unsafe extern "C" {
+    #[doc = " Counts each byte value in `data`. Loads 16 bytes at a time and counts them\n into 4 tables, so that runs of the same byte don't wait on each other.\n\n @ir slice(data, len)\n @ir out(counts, 256)"]
+    pub fn byte_histogram_sse2(data: *const u8, len: usize, counts: *mut u32);
+}
+unsafe extern "C" {
+    #[doc = " `byte_histogram_sse2`, loading 32 bytes at a time.\n\n @ir slice(data, len)\n @ir out(counts, 256)\n @ir target_feature(avx2)"]
+    pub fn byte_histogram_avx2(data: *const u8, len: usize, counts: *mut u32);
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/simd.rs"));
+}
+
+/// Bindings for `c_src/structs.h`.
+pub mod structs {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of Vec2"][::std::mem::size_of::<Vec2>() - 8usize];
+    ["Alignment of Vec2"][::std::mem::align_of::<Vec2>() - 4usize];
+    ["Offset of field: Vec2::x"][::std::mem::offset_of!(Vec2, x) - 0usize];
+    ["Offset of field: Vec2::y"][::std::mem::offset_of!(Vec2, y) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Sample {
+    pub id: i32,
+    pub weight: f32,
+    pub value: f64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of Sample"][::std::mem::size_of::<Sample>() - 16usize];
+    ["Alignment of Sample"][::std::mem::align_of::<Sample>() - 8usize];
+    ["Offset of field: Sample::id"][::std::mem::offset_of!(Sample, id) - 0usize];
+    ["Offset of field: Sample::weight"][::std::mem::offset_of!(Sample, weight) - 4usize];
+    ["Offset of field: Sample::value"][::std::mem::offset_of!(Sample, value) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Block {
+    pub values: [f64; 8usize],
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of Block"][::std::mem::size_of::<Block>() - 64usize];
+    ["Alignment of Block"][::std::mem::align_of::<Block>() - 8usize];
+    ["Offset of field: Block::values"][::std::mem::offset_of!(Block, values) - 0usize];
+};
+unsafe extern "C" {
+    pub fn vec2_add(a: Vec2, b: Vec2) -> Vec2;
+}
+unsafe extern "C" {
+    pub fn sample_scale(s: Sample, k: f32) -> Sample;
+}
+unsafe extern "C" {
+    pub fn block_add(a: Block, b: Block) -> Block;
+}
+unsafe extern "C" {
+    pub fn block_splat(x: f64) -> Block;
+}
+unsafe extern "C" {
+    #[doc = " @ir ref(a)\n @ir ref(b)"]
+    pub fn vec2_dot(a: *const Vec2, b: *const Vec2) -> f32;
+}
+unsafe extern "C" {
+    #[doc = " @ir ref(s)"]
+    pub fn sample_scale_in_place(s: *mut Sample, k: f32);
+}
+unsafe extern "C" {
+    #[doc = " @ir ref(b)"]
+    pub fn block_sum(b: *const Block) -> f64;
+}
+unsafe extern "C" {
+    #[doc = " @ir ref(a)\n @ir ref(b)\n @ir out(result)"]
+    pub fn block_add_into(a: *const Block, b: *const Block, result: *mut Block);
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/structs.rs"));
+}
+
+/// Bindings for `c_src/unions.h`.
+pub mod unions {
+/* automatically generated by rust-bindgen 0.72.1 */
+
+pub const ValueKind_VALUE_INT: ValueKind = 0;
+pub const ValueKind_VALUE_FLOAT: ValueKind = 1;
+pub const ValueKind_VALUE_PAIR: ValueKind = 2;
+pub type ValueKind = ::std::os::raw::c_uint;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Pair {
+    pub a: i32,
+    pub b: i32,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of Pair"][::std::mem::size_of::<Pair>() - 8usize];
+    ["Alignment of Pair"][::std::mem::align_of::<Pair>() - 4usize];
+    ["Offset of field: Pair::a"][::std::mem::offset_of!(Pair, a) - 0usize];
+    ["Offset of field: Pair::b"][::std::mem::offset_of!(Pair, b) - 4usize];
+};
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union ValueData {
+    pub i: i64,
+    pub f: f64,
+    pub pair: Pair,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of ValueData"][::std::mem::size_of::<ValueData>() - 8usize];
+    ["Alignment of ValueData"][::std::mem::align_of::<ValueData>() - 8usize];
+    ["Offset of field: ValueData::i"][::std::mem::offset_of!(ValueData, i) - 0usize];
+    ["Offset of field: ValueData::f"][::std::mem::offset_of!(ValueData, f) - 0usize];
+    ["Offset of field: ValueData::pair"][::std::mem::offset_of!(ValueData, pair) - 0usize];
+};
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Value {
+    pub kind: ValueKind,
+    pub data: ValueData,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of Value"][::std::mem::size_of::<Value>() - 16usize];
+    ["Alignment of Value"][::std::mem::align_of::<Value>() - 8usize];
+    ["Offset of field: Value::kind"][::std::mem::offset_of!(Value, kind) - 0usize];
+    ["Offset of field: Value::data"][::std::mem::offset_of!(Value, data) - 8usize];
+};
+unsafe extern "C" {
+    pub fn value_int(i: i64) -> Value;
+}
+unsafe extern "C" {
+    pub fn value_float(f: f64) -> Value;
+}
+unsafe extern "C" {
+    pub fn value_pair(a: i32, b: i32) -> Value;
+}
+unsafe extern "C" {
+    pub fn value_to_double(v: Value) -> f64;
+}
+unsafe extern "C" {
+    #[doc = " @ir slice(values, len)"]
+    pub fn value_sum(values: *const Value, len: usize) -> f64;
+}
+
+include!(concat!(env!("OUT_DIR"), "/safe_wrappers/unions.rs"));
+}
+
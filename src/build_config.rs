@@ -0,0 +1,25 @@
+//! The LTO mode and compiler flags this crate was built with, as resolved
+//! by `build.rs` from `IR_LTO_MODE` and `IR_C_OPT`. Tag IR dumps and
+//! benchmark results with it so they can be traced back to their build.
+
+/// The resolved build configuration. See [`BUILD_CONFIG`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildConfig {
+    /// One of `off`, `thin-local`, `thin`, `fat` or `cross-language`.
+    pub lto_mode: &'static str,
+    /// The optimization level the C sources were compiled with.
+    pub c_opt_level: &'static str,
+    /// The C compiler `cc` invoked.
+    pub c_compiler: &'static str,
+    /// Every flag the C sources were compiled with.
+    pub c_flags: &'static [&'static str],
+    /// The RUSTFLAGS the LTO mode requires on the Rust side.
+    pub rustflags: &'static [&'static str],
+    /// Extra arguments passed to the linker.
+    pub link_args: &'static [&'static str],
+}
+
+include!(concat!(env!("OUT_DIR"), "/build_config.rs"));
+
+/// [`BUILD_CONFIG`] as written to `$OUT_DIR/build_config.json`.
+pub const BUILD_CONFIG_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/build_config.json"));
@@ -2,5 +2,14 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(dead_code)]
+// Bindgen's helpers for bitfields and flexible array members.
+#![allow(clippy::missing_safety_doc)]
+#![allow(clippy::useless_transmute)]
+#![allow(clippy::ptr_offset_with_cast)]
 
+#[cfg(not(feature = "pregenerated-bindings"))]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(feature = "pregenerated-bindings")]
+include!("bindings_pregenerated.rs");
+
+pub mod checked;
@@ -0,0 +1,141 @@
+//! Wrappers that check the preconditions of the C functions whose misuse is
+//! undefined behavior, and return a [`PreconditionError`] instead of calling
+//! them when a precondition fails.
+//!
+//! Unlike the `<fn>_safe` wrappers, which rule out bad pointers through their
+//! types and leave arithmetic to the C function, these take plain slices and
+//! check everything the C function assumes: that the signed arithmetic of
+//! `inc` or `factorial` doesn't overflow, and that a pointer has as many
+//! elements as the function reads or writes.
+//!
+//! The preconditions of each function are declared in the table below, as
+//! conditions that must hold and the error if one doesn't. The checks run in
+//! Rust, so wherever the caller's inputs are provably in range, inlining
+//! (across crates, LTO) can remove them.
+
+use std::fmt;
+use std::os::raw::c_int;
+
+use super::{enums, input, math_ops};
+
+/// A precondition of a C function that the arguments don't meet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreconditionError {
+    /// The result would overflow the return type, which is undefined
+    /// behavior for the signed integers of C.
+    Overflow,
+    /// `param` has `len` elements, but the function accesses `needed`.
+    TooShort {
+        param: &'static str,
+        len: usize,
+        needed: usize,
+    },
+    /// Slices that the function reads in step have different lengths.
+    LengthMismatch { len: usize, other_len: usize },
+}
+
+impl fmt::Display for PreconditionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreconditionError::Overflow => f.write_str("the result overflows"),
+            PreconditionError::TooShort { param, len, needed } => {
+                write!(f, "`{param}` has {len} elements, but {needed} are needed")
+            }
+            PreconditionError::LengthMismatch { len, other_len } => {
+                write!(
+                    f,
+                    "the slices have different lengths: {len} and {other_len}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PreconditionError {}
+
+/// Defines one wrapper per table entry:
+///
+/// ```text
+/// pub fn name(params) -> Ret {
+///     condition => error,
+///     ...
+/// } = call;
+/// ```
+///
+/// The wrapper returns `Result<Ret, PreconditionError>`, the first error
+/// whose condition doesn't hold, or the result of `call` made in an
+/// `unsafe` block.
+macro_rules! preconditions {
+    ($(
+        $(#[$attr:meta])*
+        pub fn $name:ident($($param:ident: $ty:ty),* $(,)?) -> $ret:ty {
+            $($condition:expr => $error:expr),* $(,)?
+        } = $call:expr;
+    )*) => {$(
+        $(#[$attr])*
+        pub fn $name($($param: $ty),*) -> Result<$ret, PreconditionError> {
+            $(
+                if !$condition {
+                    return Err($error);
+                }
+            )*
+            // SAFETY: the preconditions rule out the undefined behavior.
+            Ok(unsafe { $call })
+        }
+    )*};
+}
+
+fn too_short(param: &'static str, slice: &[f64], needed: usize) -> PreconditionError {
+    PreconditionError::TooShort {
+        param,
+        len: slice.len(),
+        needed,
+    }
+}
+
+preconditions! {
+    /// [`input::inc`], which overflows on `INT_MAX`.
+    pub fn inc(x: c_int) -> c_int {
+        x != c_int::MAX => PreconditionError::Overflow,
+    } = input::inc(x);
+
+    /// [`input::dec`], which overflows on `INT_MIN`.
+    pub fn dec(x: c_int) -> c_int {
+        x != c_int::MIN => PreconditionError::Overflow,
+    } = input::dec(x);
+
+    /// [`math_ops::factorial`], which overflows from 13! on.
+    pub fn factorial(n: i32) -> i32 {
+        n <= 12 => PreconditionError::Overflow,
+    } = math_ops::factorial(n);
+
+    /// [`math_ops::vector_dot_product`], which reads `a.len()` elements of
+    /// both.
+    pub fn vector_dot_product(a: &[f64], b: &[f64]) -> f64 {
+        a.len() == b.len() => PreconditionError::LengthMismatch {
+            len: a.len(),
+            other_len: b.len(),
+        },
+    } = math_ops::vector_dot_product(a.as_ptr(), b.as_ptr(), a.len());
+
+    /// [`math_ops::matrix_multiply_2x2`], which reads 4 elements of `a` and
+    /// `b` and writes 4 to `result`.
+    pub fn matrix_multiply_2x2(a: &[f64], b: &[f64], result: &mut [f64]) -> () {
+        a.len() >= 4 => too_short("a", a, 4),
+        b.len() >= 4 => too_short("b", b, 4),
+        result.len() >= 4 => too_short("result", result, 4),
+    } = math_ops::matrix_multiply_2x2(a.as_ptr(), b.as_ptr(), result.as_mut_ptr());
+
+    /// [`enums::apply_op`], whose arithmetic overflows like Rust's, except
+    /// that dividing by zero gives 0.
+    pub fn apply_op(op: enums::Op, a: c_int, b: c_int) -> c_int {
+        match op {
+            enums::Op_OP_ADD => a.checked_add(b).is_some(),
+            enums::Op_OP_SUB => a.checked_sub(b).is_some(),
+            enums::Op_OP_MUL => a.checked_mul(b).is_some(),
+            enums::Op_OP_DIV => b == 0 || a.checked_div(b).is_some(),
+            enums::Op_OP_NEG => a != c_int::MIN,
+            _ => true,
+        } => PreconditionError::Overflow,
+    } = enums::apply_op(op, a, b);
+}
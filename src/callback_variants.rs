@@ -0,0 +1,41 @@
+//! One instance of each callback wrapper variant, and of the port, for the
+//! same closures. The wrappers are generic over the closure, so without
+//! these the library IR would only have the variants `Kernels` uses.
+//!
+//! Compare their landing pads with `ir_report --filter callback_variants`.
+
+use std::any::Any;
+
+use crate::{c_ffi, native};
+
+pub fn sort_ints_safe(values: &mut [i32]) {
+    c_ffi::callbacks::sort_ints_safe(values, |a, b| a.cmp(b) as i32)
+}
+
+pub fn sort_ints_catch_unwind(values: &mut [i32]) -> Result<(), Box<dyn Any + Send>> {
+    c_ffi::callbacks::sort_ints_catch_unwind(values, |a, b| a.cmp(b) as i32)
+}
+
+pub fn sort_ints_c_unwind(values: &mut [i32]) -> Result<(), Box<dyn Any + Send>> {
+    c_ffi::callbacks::sort_ints_c_unwind(values, |a, b| a.cmp(b) as i32)
+}
+
+pub fn sort_ints_native(values: &mut [i32]) {
+    native::callbacks::sort_ints(values, |a, b| a.cmp(b) as i32)
+}
+
+pub fn map_array_safe(values: &mut [i32]) {
+    c_ffi::callbacks::map_array_safe(values, |x| x.wrapping_mul(3))
+}
+
+pub fn map_array_catch_unwind(values: &mut [i32]) -> Result<(), Box<dyn Any + Send>> {
+    c_ffi::callbacks::map_array_catch_unwind(values, |x| x.wrapping_mul(3))
+}
+
+pub fn map_array_c_unwind(values: &mut [i32]) -> Result<(), Box<dyn Any + Send>> {
+    c_ffi::callbacks::map_array_c_unwind(values, |x| x.wrapping_mul(3))
+}
+
+pub fn map_array_native(values: &mut [i32]) {
+    native::callbacks::map_array(values, |x| x.wrapping_mul(3))
+}
@@ -0,0 +1,343 @@
+//! Counts what the IR comparisons look at, per function, in textual LLVM IR
+//! (`.ll` files).
+//!
+//! This is a line-based reader for the output of `--emit=llvm-ir`,
+//! `clang -S -emit-llvm` and `llvm-dis`, not a full IR parser: each
+//! instruction is one line, and a function runs from its `define` line to
+//! the closing `}`.
+
+/// A function defined in an IR module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Function {
+    /// The symbol, e.g. `_ZN13ir_comparison6kernels...E`.
+    pub symbol: String,
+    /// The symbol with Rust's legacy mangling undone; C symbols as they are.
+    pub name: String,
+    pub stats: FunctionStats,
+    /// The value the function returns if its body folded down to a single
+    /// `ret` of a constant, e.g. `i32 2048`.
+    pub constant: Option<String>,
+}
+
+/// Instruction counts of one function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FunctionStats {
+    pub instructions: usize,
+    /// `call` instructions, including intrinsics.
+    pub calls: usize,
+    /// Calls that can unwind into a landing pad.
+    pub invokes: usize,
+    /// Landing pads: the cleanup and catch blocks that unwinding runs.
+    pub landing_pads: usize,
+    /// Conditional `br`s and `switch`es, e.g. the checks that inlining
+    /// failed to fold away.
+    pub branches: usize,
+    /// `add`, `sub`, `mul` and `shl` with `nsw` or `nuw`, which let the
+    /// optimizer assume they don't overflow.
+    pub no_wrap: usize,
+    /// Calls to the `llvm.*.with.overflow` intrinsics, which overflow checks
+    /// and the `__builtin_*_overflow` functions lower to.
+    pub overflow_intrinsics: usize,
+    /// The width in bits of the widest vector the function loads or stores,
+    /// e.g. 128 for `<4 x float>` and 256 for `<32 x i8>`; 0 for none. The
+    /// index and mask vectors of a vectorized loop don't count.
+    pub vector_bits: u32,
+}
+
+/// The functions defined in `ir`, in order.
+pub fn functions(ir: &str) -> Vec<Function> {
+    let mut functions = Vec::new();
+    let mut current: Option<Function> = None;
+    for line in ir.lines() {
+        if let Some(function) = &mut current {
+            if line.starts_with('}') {
+                if function.stats.instructions != 1 {
+                    function.constant = None;
+                }
+                functions.extend(current.take());
+            } else if let Some(opcode) = opcode(line) {
+                let stats = &mut function.stats;
+                stats.instructions += 1;
+                let memory = matches!(opcode, "load" | "store")
+                    || line.contains("@llvm.masked.load.")
+                    || line.contains("@llvm.masked.store.");
+                if memory {
+                    stats.vector_bits = stats.vector_bits.max(vector_bits(line));
+                }
+                match opcode {
+                    "call" if line.contains(".with.overflow.") => {
+                        stats.calls += 1;
+                        stats.overflow_intrinsics += 1;
+                    }
+                    "call" => stats.calls += 1,
+                    "invoke" => stats.invokes += 1,
+                    "landingpad" => stats.landing_pads += 1,
+                    "ret" => function.constant = returned_constant(line),
+                    "br" if line.contains("br i1 ") => stats.branches += 1,
+                    "switch" => stats.branches += 1,
+                    "add" | "sub" | "mul" | "shl"
+                        if line
+                            .split_whitespace()
+                            .any(|word| word == "nsw" || word == "nuw") =>
+                    {
+                        stats.no_wrap += 1
+                    }
+                    _ => {}
+                }
+            }
+        } else if line.starts_with("define ")
+            && let Some(symbol) = defined_symbol(line)
+        {
+            current = Some(Function {
+                name: demangle(&symbol),
+                symbol,
+                stats: FunctionStats::default(),
+                constant: None,
+            });
+        }
+    }
+    functions
+}
+
+/// How an integer kernel handles overflow, as the prefix of its name tells:
+/// `inc`, `checked_inc`, `wrapping_inc` or `saturating_inc`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Overflow {
+    /// The kernel itself. Overflow is undefined behavior in C; in Rust it
+    /// panics with overflow checks on and wraps with them off.
+    Unchecked,
+    /// Reports the overflow, as `None` in Rust.
+    Checked,
+    /// Wraps around.
+    Wrapping,
+    /// Clamps to the bounds of the type.
+    Saturating,
+}
+
+impl Overflow {
+    const PREFIXES: [(Overflow, &str); 3] = [
+        (Overflow::Checked, "checked_"),
+        (Overflow::Wrapping, "wrapping_"),
+        (Overflow::Saturating, "saturating_"),
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Overflow::Unchecked => "unchecked",
+            Overflow::Checked => "checked",
+            Overflow::Wrapping => "wrapping",
+            Overflow::Saturating => "saturating",
+        }
+    }
+}
+
+/// A function of an integer kernel, tagged with its overflow variant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverflowVariant<'a> {
+    /// The name of the kernel, e.g. `inc`.
+    pub kernel: &'a str,
+    pub overflow: Overflow,
+    pub function: &'a Function,
+}
+
+/// The integer kernels and their overflow variants in `functions`, sorted
+/// by kernel and variant. A kernel is anything with a `checked_`,
+/// `wrapping_` or `saturating_` variant; the kernel itself is the function
+/// without the prefix. Only the last segment of a path counts, without a
+/// static function shim's suffix after `__`, so
+/// `ir_comparison::rust_port::input::checked_inc` and
+/// `checked_inc__input_extern` both tag `inc` as `Checked`.
+pub fn overflow_variants(functions: &[Function]) -> Vec<OverflowVariant<'_>> {
+    let names: Vec<&str> = functions
+        .iter()
+        .map(|function| {
+            let name = function.name.rsplit("::").next().unwrap_or_default();
+            name.split("__").next().unwrap_or_default()
+        })
+        .collect();
+    let kernels: Vec<&str> = names
+        .iter()
+        .filter_map(|name| tagged(name).map(|(_, kernel)| kernel))
+        .collect();
+    let mut variants: Vec<OverflowVariant> = names
+        .iter()
+        .zip(functions)
+        .filter_map(|(name, function)| {
+            let (overflow, kernel) = tagged(name).unwrap_or((Overflow::Unchecked, name));
+            let kernel = *kernels.iter().find(|k| **k == kernel)?;
+            Some(OverflowVariant {
+                kernel,
+                overflow,
+                function,
+            })
+        })
+        .collect();
+    variants.sort_by_key(|variant| (variant.kernel, variant.overflow));
+    variants
+}
+
+/// The variant and kernel a prefixed name like `checked_inc` names.
+fn tagged(name: &str) -> Option<(Overflow, &str)> {
+    Overflow::PREFIXES
+        .into_iter()
+        .find_map(|(overflow, prefix)| Some((overflow, name.strip_prefix(prefix)?)))
+}
+
+/// The symbol a `define` line defines.
+fn defined_symbol(line: &str) -> Option<String> {
+    let rest = &line[line.find('@')? + 1..];
+    match rest.strip_prefix('"') {
+        Some(quoted) => Some(quoted[..quoted.find('"')?].to_owned()),
+        None => {
+            let end = rest.find('(')?;
+            Some(rest[..end].to_owned())
+        }
+    }
+}
+
+/// The operand of a `ret` line if it is a constant scalar, e.g. `i32 2048`
+/// or `double 2.560000e+02`.
+fn returned_constant(line: &str) -> Option<String> {
+    let operand = line.trim().strip_prefix("ret ")?;
+    // Drop attached metadata, e.g. `, !dbg !12`.
+    let operand = operand.split(", !").next()?.trim();
+    let (ty, value) = operand.split_once(' ')?;
+    let constant = value.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+        || matches!(value, "true" | "false" | "null");
+    constant.then(|| format!("{ty} {value}"))
+}
+
+/// The width of the widest fixed-size vector type on an instruction line.
+/// Vectors of `i1`, the masks of masked loads and stores, don't count.
+fn vector_bits(line: &str) -> u32 {
+    line.match_indices('<')
+        .filter_map(|(start, _)| {
+            let (lanes, rest) = line[start + 1..].split_once(" x ")?;
+            let lanes: u32 = lanes.parse().ok()?;
+            let bits = match rest.split('>').next()? {
+                "half" | "bfloat" => 16,
+                "float" => 32,
+                "double" | "ptr" => 64,
+                ty => ty
+                    .strip_prefix('i')?
+                    .parse()
+                    .ok()
+                    .filter(|&bits| bits > 1)?,
+            };
+            Some(lanes * bits)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// The opcode of an instruction line, or `None` for labels, comments,
+/// metadata, blank lines and the continuations of multi-line instructions.
+fn opcode(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with(';') || line.starts_with('!') || line.ends_with(':') {
+        return None;
+    }
+    // A label with a comment after it, e.g. `bb3:  ; preds = %start`.
+    if let Some((label, _)) = line.split_once(';')
+        && label.trim_end().ends_with(':')
+    {
+        return None;
+    }
+    let instruction = match line.split_once(" = ") {
+        Some((result, instruction)) if result.starts_with('%') => instruction,
+        _ => line,
+    };
+    let mut words = instruction.split_whitespace();
+    let mut opcode = words.next()?;
+    if matches!(opcode, "tail" | "musttail" | "notail") {
+        opcode = words.next()?;
+    }
+    // The continuation lines of `invoke`, `landingpad` and `switch`.
+    let continuation = matches!(opcode, "to" | "cleanup" | "catch" | "filter" | "]")
+        || opcode
+            .strip_prefix('i')
+            .is_some_and(|bits| bits.chars().all(|c| c.is_ascii_digit()));
+    if continuation {
+        return None;
+    }
+    Some(opcode)
+}
+
+/// Undoes Rust's legacy symbol mangling (`_ZN...E`), dropping the hash.
+/// Other symbols, including v0-mangled ones (`_R...`), are returned as they
+/// are.
+pub fn demangle(symbol: &str) -> String {
+    let Some(mut rest) = symbol.strip_prefix("_ZN") else {
+        return symbol.to_owned();
+    };
+    let mut segments = Vec::new();
+    while !rest.starts_with('E') {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let Ok(len) = rest[..digits].parse::<usize>() else {
+            return symbol.to_owned();
+        };
+        let Some(segment) = rest.get(digits..digits + len) else {
+            return symbol.to_owned();
+        };
+        segments.push(segment);
+        rest = &rest[digits + len..];
+    }
+    if segments.last().is_some_and(|last| is_hash(last)) {
+        segments.pop();
+    }
+    segments
+        .iter()
+        .map(|segment| unescape(segment))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn is_hash(segment: &str) -> bool {
+    segment.len() == 17
+        && segment.starts_with('h')
+        && segment[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Replaces the `$..$` escapes and `..` of a legacy-mangled path segment.
+fn unescape(segment: &str) -> String {
+    let segment = segment
+        .strip_prefix('_')
+        .filter(|s| s.starts_with('$'))
+        .unwrap_or(segment);
+    let mut out = String::new();
+    let mut rest = segment;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            out.push_str("::");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('$')
+            && let Some(end) = after.find('$')
+        {
+            let escape = &after[..end];
+            match escape {
+                "SP" => out.push('@'),
+                "BP" => out.push('*'),
+                "RF" => out.push('&'),
+                "LT" => out.push('<'),
+                "GT" => out.push('>'),
+                "LP" => out.push('('),
+                "RP" => out.push(')'),
+                "C" => out.push(','),
+                _ => match escape
+                    .strip_prefix('u')
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32)
+                {
+                    Some(c) => out.push(c),
+                    None => out.push_str(&rest[..end + 2]),
+                },
+            }
+            rest = &after[end + 1..];
+        } else {
+            let c = rest.chars().next().unwrap();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
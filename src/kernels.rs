@@ -0,0 +1,810 @@
+//! One interface over the C kernels and their Rust ports, so that tests and
+//! benchmarks are written once and run against either backend.
+
+use std::alloc::{self, Layout};
+use std::mem::{self, offset_of};
+use std::ptr::{self, NonNull};
+
+use crate::native::bitfields::Instr;
+use crate::native::enums::{Op, Shape};
+use crate::native::flex::Series;
+use crate::native::structs::{Block, Sample, Vec2};
+use crate::native::unions::Value;
+use crate::{c_ffi, native};
+
+/// The kernels, one method per C function, with the signature of its port
+/// in `native`.
+pub trait Kernels {
+    /// A short name for reports, e.g. `c`.
+    fn name(&self) -> &'static str;
+
+    fn inc(&self, x: i32) -> i32;
+    fn dec(&self, x: i32) -> i32;
+    fn checked_inc(&self, x: i32) -> Option<i32>;
+    fn wrapping_inc(&self, x: i32) -> i32;
+    fn saturating_inc(&self, x: i32) -> i32;
+    fn checked_dec(&self, x: i32) -> Option<i32>;
+    fn wrapping_dec(&self, x: i32) -> i32;
+    fn saturating_dec(&self, x: i32) -> i32;
+
+    fn factorial(&self, n: i32) -> i32;
+    fn checked_factorial(&self, n: i32) -> Option<i32>;
+    fn wrapping_factorial(&self, n: i32) -> i32;
+    fn saturating_factorial(&self, n: i32) -> i32;
+    fn dot(&self, a: &[f64], b: &[f64]) -> f64;
+    fn is_prime(&self, n: u32) -> bool;
+    fn matrix_multiply_2x2(&self, a: &[f64; 4], b: &[f64; 4]) -> [f64; 4];
+
+    fn vec2_add(&self, a: Vec2, b: Vec2) -> Vec2;
+    fn sample_scale(&self, s: Sample, k: f32) -> Sample;
+    fn block_add(&self, a: Block, b: Block) -> Block;
+    fn block_splat(&self, x: f64) -> Block;
+    fn vec2_dot(&self, a: &Vec2, b: &Vec2) -> f32;
+    fn sample_scale_in_place(&self, s: &mut Sample, k: f32);
+    fn block_sum(&self, b: &Block) -> f64;
+    fn block_add_into(&self, a: &Block, b: &Block) -> Block;
+
+    // Callbacks are trait objects rather than `impl FnMut`, so that
+    // `Kernels` stays object safe.
+    fn sort_ints(&self, values: &mut [i32], compare: &mut dyn FnMut(&i32, &i32) -> i32);
+    fn map_array(&self, values: &mut [i32], f: &mut dyn FnMut(i32) -> i32);
+
+    fn shape_sides(&self, shape: Shape) -> i32;
+    fn shape_area(&self, shape: Shape, size: f64) -> f64;
+    fn shape_next(&self, shape: Shape) -> Shape;
+    fn apply_op(&self, op: Op, a: i32, b: i32) -> i32;
+    fn checked_apply_op(&self, op: Op, a: i32, b: i32) -> Option<i32>;
+    fn wrapping_apply_op(&self, op: Op, a: i32, b: i32) -> i32;
+    fn saturating_apply_op(&self, op: Op, a: i32, b: i32) -> i32;
+
+    fn instr_encode(&self, opcode: u32, dest: u32, src: u32, imm: u32) -> Instr;
+    fn instr_opcode(&self, instr: Instr) -> u32;
+    fn instr_imm(&self, instr: Instr) -> u32;
+    fn instr_set_dest(&self, instr: &mut Instr, dest: u32);
+    fn instr_sum_imm(&self, instrs: &[Instr]) -> u32;
+
+    fn value_int(&self, i: i64) -> Value;
+    fn value_float(&self, f: f64) -> Value;
+    fn value_pair(&self, a: i32, b: i32) -> Value;
+    fn value_to_double(&self, v: Value) -> f64;
+    fn value_sum(&self, values: &[Value]) -> f64;
+
+    fn series_sum(&self, s: &Series) -> f64;
+    fn series_scale(&self, s: &mut Series, k: f64);
+
+    fn clamp_int(&self, x: i32, lo: i32, hi: i32) -> i32;
+    fn in_range(&self, x: i32, lo: i32, hi: i32) -> i32;
+    fn buffer_bytes(&self) -> i32;
+    fn scaled_buffer_bytes(&self) -> f64;
+    fn set_flag(&self, flags: u32) -> u32;
+    fn next_color(&self, color: i32) -> i32;
+    fn clamp(&self, x: i32, lo: i32, hi: i32) -> i32;
+    fn clamp_temp(&self, t: i32) -> i32;
+    fn buffer_offset(&self, i: usize) -> usize;
+
+    // x86-64 only. The `_avx2` kernels panic on CPUs without AVX2.
+    #[cfg(target_arch = "x86_64")]
+    fn saxpy_sse2(&self, a: f32, x: &[f32], y: &mut [f32]);
+    #[cfg(target_arch = "x86_64")]
+    fn saxpy_avx2(&self, a: f32, x: &[f32], y: &mut [f32]);
+    #[cfg(target_arch = "x86_64")]
+    fn sum_sse2(&self, x: &[f32]) -> f32;
+    #[cfg(target_arch = "x86_64")]
+    fn sum_avx2(&self, x: &[f32]) -> f32;
+    #[cfg(target_arch = "x86_64")]
+    fn byte_histogram_sse2(&self, data: &[u8]) -> [u32; 256];
+    #[cfg(target_arch = "x86_64")]
+    fn byte_histogram_avx2(&self, data: &[u8]) -> [u32; 256];
+}
+
+/// The C kernels, called through the safe wrappers in `c_ffi`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CBackend;
+
+impl Kernels for CBackend {
+    fn name(&self) -> &'static str {
+        "c"
+    }
+
+    fn inc(&self, x: i32) -> i32 {
+        c_ffi::input::inc_safe(x)
+    }
+
+    fn dec(&self, x: i32) -> i32 {
+        c_ffi::input::dec_safe(x)
+    }
+
+    fn checked_inc(&self, x: i32) -> Option<i32> {
+        c_ffi::input::checked_inc_safe(x)
+    }
+
+    fn wrapping_inc(&self, x: i32) -> i32 {
+        c_ffi::input::wrapping_inc_safe(x)
+    }
+
+    fn saturating_inc(&self, x: i32) -> i32 {
+        c_ffi::input::saturating_inc_safe(x)
+    }
+
+    fn checked_dec(&self, x: i32) -> Option<i32> {
+        c_ffi::input::checked_dec_safe(x)
+    }
+
+    fn wrapping_dec(&self, x: i32) -> i32 {
+        c_ffi::input::wrapping_dec_safe(x)
+    }
+
+    fn saturating_dec(&self, x: i32) -> i32 {
+        c_ffi::input::saturating_dec_safe(x)
+    }
+
+    fn factorial(&self, n: i32) -> i32 {
+        c_ffi::math_ops::factorial_safe(n)
+    }
+
+    fn checked_factorial(&self, n: i32) -> Option<i32> {
+        c_ffi::math_ops::checked_factorial_safe(n)
+    }
+
+    fn wrapping_factorial(&self, n: i32) -> i32 {
+        c_ffi::math_ops::wrapping_factorial_safe(n)
+    }
+
+    fn saturating_factorial(&self, n: i32) -> i32 {
+        c_ffi::math_ops::saturating_factorial_safe(n)
+    }
+
+    fn dot(&self, a: &[f64], b: &[f64]) -> f64 {
+        c_ffi::math_ops::vector_dot_product_safe(a, b)
+    }
+
+    fn is_prime(&self, n: u32) -> bool {
+        c_ffi::math_ops::is_prime_safe(n)
+    }
+
+    fn matrix_multiply_2x2(&self, a: &[f64; 4], b: &[f64; 4]) -> [f64; 4] {
+        c_ffi::math_ops::matrix_multiply_2x2_safe(a, b)
+    }
+
+    fn vec2_add(&self, a: Vec2, b: Vec2) -> Vec2 {
+        Vec2::from_c(c_ffi::structs::vec2_add_safe(a.to_c(), b.to_c()))
+    }
+
+    fn sample_scale(&self, s: Sample, k: f32) -> Sample {
+        Sample::from_c(c_ffi::structs::sample_scale_safe(s.to_c(), k))
+    }
+
+    fn block_add(&self, a: Block, b: Block) -> Block {
+        Block::from_c(c_ffi::structs::block_add_safe(a.to_c(), b.to_c()))
+    }
+
+    fn block_splat(&self, x: f64) -> Block {
+        Block::from_c(c_ffi::structs::block_splat_safe(x))
+    }
+
+    fn vec2_dot(&self, a: &Vec2, b: &Vec2) -> f32 {
+        c_ffi::structs::vec2_dot_safe(a.as_c(), b.as_c())
+    }
+
+    fn sample_scale_in_place(&self, s: &mut Sample, k: f32) {
+        c_ffi::structs::sample_scale_in_place_safe(s.as_c_mut(), k)
+    }
+
+    fn block_sum(&self, b: &Block) -> f64 {
+        c_ffi::structs::block_sum_safe(b.as_c())
+    }
+
+    fn block_add_into(&self, a: &Block, b: &Block) -> Block {
+        Block::from_c(c_ffi::structs::block_add_into_safe(a.as_c(), b.as_c()))
+    }
+
+    fn sort_ints(&self, values: &mut [i32], compare: &mut dyn FnMut(&i32, &i32) -> i32) {
+        c_ffi::callbacks::sort_ints_safe(values, compare)
+    }
+
+    fn map_array(&self, values: &mut [i32], f: &mut dyn FnMut(i32) -> i32) {
+        c_ffi::callbacks::map_array_safe(values, f)
+    }
+
+    // Through the bindings in `enum_style`; the native enums have the C
+    // values as discriminants.
+    fn shape_sides(&self, shape: Shape) -> i32 {
+        c_ffi::enums::shape_sides_safe(shape as c_ffi::enums::Shape)
+    }
+
+    fn shape_area(&self, shape: Shape, size: f64) -> f64 {
+        c_ffi::enums::shape_area_safe(shape as c_ffi::enums::Shape, size)
+    }
+
+    fn shape_next(&self, shape: Shape) -> Shape {
+        use c_ffi::enums as c;
+        match c::shape_next_safe(shape as c::Shape) {
+            c::Shape_SHAPE_CIRCLE => Shape::Circle,
+            c::Shape_SHAPE_SQUARE => Shape::Square,
+            c::Shape_SHAPE_TRIANGLE => Shape::Triangle,
+            c::Shape_SHAPE_HEXAGON => Shape::Hexagon,
+            other => panic!("shape_next returned {other}, which is no Shape"),
+        }
+    }
+
+    fn apply_op(&self, op: Op, a: i32, b: i32) -> i32 {
+        c_ffi::enums::apply_op_safe(op as c_ffi::enums::Op, a, b)
+    }
+
+    fn checked_apply_op(&self, op: Op, a: i32, b: i32) -> Option<i32> {
+        c_ffi::enums::checked_apply_op_safe(op as c_ffi::enums::Op, a, b)
+    }
+
+    fn wrapping_apply_op(&self, op: Op, a: i32, b: i32) -> i32 {
+        c_ffi::enums::wrapping_apply_op_safe(op as c_ffi::enums::Op, a, b)
+    }
+
+    fn saturating_apply_op(&self, op: Op, a: i32, b: i32) -> i32 {
+        c_ffi::enums::saturating_apply_op_safe(op as c_ffi::enums::Op, a, b)
+    }
+
+    fn instr_encode(&self, opcode: u32, dest: u32, src: u32, imm: u32) -> Instr {
+        Instr::from_c(c_ffi::bitfields::instr_encode_safe(opcode, dest, src, imm))
+    }
+
+    fn instr_opcode(&self, instr: Instr) -> u32 {
+        c_ffi::bitfields::instr_opcode_safe(instr.to_c())
+    }
+
+    fn instr_imm(&self, instr: Instr) -> u32 {
+        c_ffi::bitfields::instr_imm_safe(instr.to_c())
+    }
+
+    fn instr_set_dest(&self, instr: &mut Instr, dest: u32) {
+        let mut c = instr.to_c();
+        c_ffi::bitfields::instr_set_dest_safe(&mut c, dest);
+        *instr = Instr::from_c(c);
+    }
+
+    // The conversions copy every element, which the Rust backend doesn't.
+    fn instr_sum_imm(&self, instrs: &[Instr]) -> u32 {
+        let c: Vec<_> = instrs.iter().map(|instr| instr.to_c()).collect();
+        c_ffi::bitfields::instr_sum_imm_safe(&c)
+    }
+
+    fn value_int(&self, i: i64) -> Value {
+        Value::from_c(c_ffi::unions::value_int_safe(i))
+    }
+
+    fn value_float(&self, f: f64) -> Value {
+        Value::from_c(c_ffi::unions::value_float_safe(f))
+    }
+
+    fn value_pair(&self, a: i32, b: i32) -> Value {
+        Value::from_c(c_ffi::unions::value_pair_safe(a, b))
+    }
+
+    fn value_to_double(&self, v: Value) -> f64 {
+        c_ffi::unions::value_to_double_safe(v.to_c())
+    }
+
+    fn value_sum(&self, values: &[Value]) -> f64 {
+        let c: Vec<_> = values.iter().map(|v| v.to_c()).collect();
+        c_ffi::unions::value_sum_safe(&c)
+    }
+
+    fn series_sum(&self, s: &Series) -> f64 {
+        // SAFETY: `CSeries` holds `len` elements after the header.
+        unsafe { c_ffi::flex::series_sum(CSeries::new(&s.data).as_ptr()) }
+    }
+
+    fn series_scale(&self, s: &mut Series, k: f64) {
+        let c = CSeries::new(&s.data);
+        // SAFETY: as above.
+        unsafe { c_ffi::flex::series_scale(c.as_ptr(), k) };
+        s.data.copy_from_slice(c.data());
+    }
+
+    fn clamp_int(&self, x: i32, lo: i32, hi: i32) -> i32 {
+        c_ffi::macros::clamp_int_safe(x, lo, hi)
+    }
+
+    fn in_range(&self, x: i32, lo: i32, hi: i32) -> i32 {
+        c_ffi::macros::in_range_safe(x, lo, hi)
+    }
+
+    fn buffer_bytes(&self) -> i32 {
+        c_ffi::macros::buffer_bytes_safe()
+    }
+
+    fn scaled_buffer_bytes(&self) -> f64 {
+        c_ffi::macros::scaled_buffer_bytes_safe()
+    }
+
+    fn set_flag(&self, flags: u32) -> u32 {
+        c_ffi::macros::set_flag_safe(flags)
+    }
+
+    fn next_color(&self, color: i32) -> i32 {
+        c_ffi::macros::next_color_safe(color)
+    }
+
+    fn clamp(&self, x: i32, lo: i32, hi: i32) -> i32 {
+        c_ffi::macros::clamp_safe(x, lo, hi)
+    }
+
+    fn clamp_temp(&self, t: i32) -> i32 {
+        c_ffi::macros::clamp_temp_safe(t)
+    }
+
+    fn buffer_offset(&self, i: usize) -> usize {
+        c_ffi::macros::buffer_offset_safe(i)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn saxpy_sse2(&self, a: f32, x: &[f32], y: &mut [f32]) {
+        c_ffi::simd::saxpy_sse2_safe(a, x, y)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn saxpy_avx2(&self, a: f32, x: &[f32], y: &mut [f32]) {
+        c_ffi::simd::saxpy_avx2_safe(a, x, y)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn sum_sse2(&self, x: &[f32]) -> f32 {
+        c_ffi::simd::sum_sse2_safe(x)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn sum_avx2(&self, x: &[f32]) -> f32 {
+        c_ffi::simd::sum_avx2_safe(x)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn byte_histogram_sse2(&self, data: &[u8]) -> [u32; 256] {
+        c_ffi::simd::byte_histogram_sse2_safe(data)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn byte_histogram_avx2(&self, data: &[u8]) -> [u32; 256] {
+        c_ffi::simd::byte_histogram_avx2_safe(data)
+    }
+}
+
+/// The Rust ports in `native`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RustBackend;
+
+impl Kernels for RustBackend {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn inc(&self, x: i32) -> i32 {
+        native::input::inc(x)
+    }
+
+    fn dec(&self, x: i32) -> i32 {
+        native::input::dec(x)
+    }
+
+    fn checked_inc(&self, x: i32) -> Option<i32> {
+        native::input::checked_inc(x)
+    }
+
+    fn wrapping_inc(&self, x: i32) -> i32 {
+        native::input::wrapping_inc(x)
+    }
+
+    fn saturating_inc(&self, x: i32) -> i32 {
+        native::input::saturating_inc(x)
+    }
+
+    fn checked_dec(&self, x: i32) -> Option<i32> {
+        native::input::checked_dec(x)
+    }
+
+    fn wrapping_dec(&self, x: i32) -> i32 {
+        native::input::wrapping_dec(x)
+    }
+
+    fn saturating_dec(&self, x: i32) -> i32 {
+        native::input::saturating_dec(x)
+    }
+
+    fn factorial(&self, n: i32) -> i32 {
+        native::math_ops::factorial(n)
+    }
+
+    fn checked_factorial(&self, n: i32) -> Option<i32> {
+        native::math_ops::checked_factorial(n)
+    }
+
+    fn wrapping_factorial(&self, n: i32) -> i32 {
+        native::math_ops::wrapping_factorial(n)
+    }
+
+    fn saturating_factorial(&self, n: i32) -> i32 {
+        native::math_ops::saturating_factorial(n)
+    }
+
+    fn dot(&self, a: &[f64], b: &[f64]) -> f64 {
+        native::math_ops::vector_dot_product(a, b)
+    }
+
+    fn is_prime(&self, n: u32) -> bool {
+        native::math_ops::is_prime(n)
+    }
+
+    fn matrix_multiply_2x2(&self, a: &[f64; 4], b: &[f64; 4]) -> [f64; 4] {
+        native::math_ops::matrix_multiply_2x2(a, b)
+    }
+
+    fn vec2_add(&self, a: Vec2, b: Vec2) -> Vec2 {
+        native::structs::vec2_add(a, b)
+    }
+
+    fn sample_scale(&self, s: Sample, k: f32) -> Sample {
+        native::structs::sample_scale(s, k)
+    }
+
+    fn block_add(&self, a: Block, b: Block) -> Block {
+        native::structs::block_add(a, b)
+    }
+
+    fn block_splat(&self, x: f64) -> Block {
+        native::structs::block_splat(x)
+    }
+
+    fn vec2_dot(&self, a: &Vec2, b: &Vec2) -> f32 {
+        native::structs::vec2_dot(a, b)
+    }
+
+    fn sample_scale_in_place(&self, s: &mut Sample, k: f32) {
+        native::structs::sample_scale_in_place(s, k)
+    }
+
+    fn block_sum(&self, b: &Block) -> f64 {
+        native::structs::block_sum(b)
+    }
+
+    fn block_add_into(&self, a: &Block, b: &Block) -> Block {
+        native::structs::block_add_into(a, b)
+    }
+
+    fn sort_ints(&self, values: &mut [i32], compare: &mut dyn FnMut(&i32, &i32) -> i32) {
+        native::callbacks::sort_ints(values, compare)
+    }
+
+    fn map_array(&self, values: &mut [i32], f: &mut dyn FnMut(i32) -> i32) {
+        native::callbacks::map_array(values, f)
+    }
+
+    fn shape_sides(&self, shape: Shape) -> i32 {
+        native::enums::shape_sides(shape)
+    }
+
+    fn shape_area(&self, shape: Shape, size: f64) -> f64 {
+        native::enums::shape_area(shape, size)
+    }
+
+    fn shape_next(&self, shape: Shape) -> Shape {
+        native::enums::shape_next(shape)
+    }
+
+    fn apply_op(&self, op: Op, a: i32, b: i32) -> i32 {
+        native::enums::apply_op(op, a, b)
+    }
+
+    fn checked_apply_op(&self, op: Op, a: i32, b: i32) -> Option<i32> {
+        native::enums::checked_apply_op(op, a, b)
+    }
+
+    fn wrapping_apply_op(&self, op: Op, a: i32, b: i32) -> i32 {
+        native::enums::wrapping_apply_op(op, a, b)
+    }
+
+    fn saturating_apply_op(&self, op: Op, a: i32, b: i32) -> i32 {
+        native::enums::saturating_apply_op(op, a, b)
+    }
+
+    fn instr_encode(&self, opcode: u32, dest: u32, src: u32, imm: u32) -> Instr {
+        native::bitfields::instr_encode(opcode, dest, src, imm)
+    }
+
+    fn instr_opcode(&self, instr: Instr) -> u32 {
+        native::bitfields::instr_opcode(instr)
+    }
+
+    fn instr_imm(&self, instr: Instr) -> u32 {
+        native::bitfields::instr_imm(instr)
+    }
+
+    fn instr_set_dest(&self, instr: &mut Instr, dest: u32) {
+        native::bitfields::instr_set_dest(instr, dest)
+    }
+
+    fn instr_sum_imm(&self, instrs: &[Instr]) -> u32 {
+        native::bitfields::instr_sum_imm(instrs)
+    }
+
+    fn value_int(&self, i: i64) -> Value {
+        native::unions::value_int(i)
+    }
+
+    fn value_float(&self, f: f64) -> Value {
+        native::unions::value_float(f)
+    }
+
+    fn value_pair(&self, a: i32, b: i32) -> Value {
+        native::unions::value_pair(a, b)
+    }
+
+    fn value_to_double(&self, v: Value) -> f64 {
+        native::unions::value_to_double(v)
+    }
+
+    fn value_sum(&self, values: &[Value]) -> f64 {
+        native::unions::value_sum(values)
+    }
+
+    fn series_sum(&self, s: &Series) -> f64 {
+        native::flex::series_sum(s)
+    }
+
+    fn series_scale(&self, s: &mut Series, k: f64) {
+        native::flex::series_scale(s, k)
+    }
+
+    fn clamp_int(&self, x: i32, lo: i32, hi: i32) -> i32 {
+        native::macros::clamp_int(x, lo, hi)
+    }
+
+    fn in_range(&self, x: i32, lo: i32, hi: i32) -> i32 {
+        native::macros::in_range(x, lo, hi)
+    }
+
+    fn buffer_bytes(&self) -> i32 {
+        native::macros::buffer_bytes()
+    }
+
+    fn scaled_buffer_bytes(&self) -> f64 {
+        native::macros::scaled_buffer_bytes()
+    }
+
+    fn set_flag(&self, flags: u32) -> u32 {
+        native::macros::set_flag(flags)
+    }
+
+    fn next_color(&self, color: i32) -> i32 {
+        native::macros::next_color(color)
+    }
+
+    fn clamp(&self, x: i32, lo: i32, hi: i32) -> i32 {
+        native::macros::clamp(x, lo, hi)
+    }
+
+    fn clamp_temp(&self, t: i32) -> i32 {
+        native::macros::clamp_temp(t)
+    }
+
+    fn buffer_offset(&self, i: usize) -> usize {
+        native::macros::buffer_offset(i)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn saxpy_sse2(&self, a: f32, x: &[f32], y: &mut [f32]) {
+        native::simd::saxpy_sse2(a, x, y)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn saxpy_avx2(&self, a: f32, x: &[f32], y: &mut [f32]) {
+        assert_avx2("saxpy_avx2");
+        // SAFETY: the CPU has AVX2.
+        unsafe { native::simd::saxpy_avx2(a, x, y) }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn sum_sse2(&self, x: &[f32]) -> f32 {
+        native::simd::sum_sse2(x)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn sum_avx2(&self, x: &[f32]) -> f32 {
+        assert_avx2("sum_avx2");
+        // SAFETY: the CPU has AVX2.
+        unsafe { native::simd::sum_avx2(x) }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn byte_histogram_sse2(&self, data: &[u8]) -> [u32; 256] {
+        native::simd::byte_histogram_sse2(data)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn byte_histogram_avx2(&self, data: &[u8]) -> [u32; 256] {
+        assert_avx2("byte_histogram_avx2");
+        // SAFETY: the CPU has AVX2.
+        unsafe { native::simd::byte_histogram_avx2(data) }
+    }
+}
+
+/// Panics like the safe wrappers of the C `_avx2` kernels if the CPU has no
+/// AVX2.
+#[cfg(target_arch = "x86_64")]
+fn assert_avx2(kernel: &str) {
+    assert!(
+        is_x86_feature_detected!("avx2"),
+        "`{kernel}` needs a CPU with `avx2`"
+    );
+}
+
+/// Every backend, for comparing them with each other.
+pub const BACKENDS: [&dyn Kernels; 2] = [&CBackend, &RustBackend];
+
+/// A struct in `native` and its bindgen twin in `c_ffi`, which `CBackend`
+/// passes to C without copying field by field.
+///
+/// # Safety
+///
+/// `Self` and `C` must have the same layout: both `#[repr(C)]`, with the same
+/// fields in the same order.
+unsafe trait Twin: Copy {
+    type C: Copy;
+
+    fn to_c(self) -> Self::C {
+        // SAFETY: same layout, by the contract of the trait.
+        unsafe { mem::transmute_copy(&self) }
+    }
+
+    fn from_c(c: Self::C) -> Self {
+        // SAFETY: as above.
+        unsafe { mem::transmute_copy(&c) }
+    }
+
+    fn as_c(&self) -> &Self::C {
+        // SAFETY: as above.
+        unsafe { &*ptr::from_ref(self).cast() }
+    }
+
+    fn as_c_mut(&mut self) -> &mut Self::C {
+        // SAFETY: as above.
+        unsafe { &mut *ptr::from_mut(self).cast() }
+    }
+}
+
+/// Implements [`Twin`] for structs that have the same fields in `native` and
+/// `c_ffi`. Bindgen and the port each check their field offsets against the
+/// C layout; this checks the sizes match too.
+macro_rules! twins {
+    ($($module:ident::$name:ident),* $(,)?) => {$(
+        const _: () = assert!(
+            mem::size_of::<native::$module::$name>() == mem::size_of::<c_ffi::$module::$name>()
+                && mem::align_of::<native::$module::$name>()
+                    == mem::align_of::<c_ffi::$module::$name>()
+        );
+
+        // SAFETY: see the assertion above and the layout checks in both modules.
+        unsafe impl Twin for native::$module::$name {
+            type C = c_ffi::$module::$name;
+        }
+    )*};
+}
+
+twins!(structs::Vec2, structs::Sample, structs::Block);
+
+/// A type in `native` whose layout differs from its bindgen counterpart in
+/// `c_ffi`, so that `CBackend` converts it field by field.
+trait Convert: Copy {
+    type C: Copy;
+
+    fn to_c(self) -> Self::C;
+    fn from_c(c: Self::C) -> Self;
+}
+
+/// Through bindgen's bitfield accessors.
+impl Convert for Instr {
+    type C = c_ffi::bitfields::Instr;
+
+    fn to_c(self) -> Self::C {
+        Self::C {
+            _bitfield_align_1: [],
+            _bitfield_1: Self::C::new_bitfield_1(
+                self.opcode.into(),
+                self.dest.into(),
+                self.src.into(),
+                self.imm.into(),
+            ),
+        }
+    }
+
+    fn from_c(c: Self::C) -> Self {
+        // The accessors return at most as many bits as the field has.
+        Instr {
+            opcode: c.opcode() as u8,
+            dest: c.dest() as u8,
+            src: c.src() as u8,
+            imm: c.imm() as u16,
+        }
+    }
+}
+
+impl Convert for Value {
+    type C = c_ffi::unions::Value;
+
+    fn to_c(self) -> Self::C {
+        use c_ffi::unions as c;
+        let (kind, data) = match self {
+            Value::Int(i) => (c::ValueKind_VALUE_INT, c::ValueData { i }),
+            Value::Float(f) => (c::ValueKind_VALUE_FLOAT, c::ValueData { f }),
+            Value::Pair(a, b) => (
+                c::ValueKind_VALUE_PAIR,
+                c::ValueData {
+                    pair: c::Pair { a, b },
+                },
+            ),
+        };
+        c::Value { kind, data }
+    }
+
+    fn from_c(v: Self::C) -> Self {
+        use c_ffi::unions as c;
+        // SAFETY: `kind` says which member of `data` was written, and every
+        // member is plain old data.
+        unsafe {
+            match v.kind {
+                c::ValueKind_VALUE_INT => Value::Int(v.data.i),
+                c::ValueKind_VALUE_FLOAT => Value::Float(v.data.f),
+                c::ValueKind_VALUE_PAIR => Value::Pair(v.data.pair.a, v.data.pair.b),
+                other => panic!("a Value of kind {other}, which is no ValueKind"),
+            }
+        }
+    }
+}
+
+/// A `c_ffi::flex::Series` allocated from Rust: the header and the elements
+/// after it in one allocation, as C would `malloc` it. Bindgen's
+/// `__IncompleteArrayField` only marks where the elements start.
+struct CSeries {
+    ptr: NonNull<c_ffi::flex::Series>,
+    layout: Layout,
+}
+
+impl CSeries {
+    const DATA: usize = offset_of!(c_ffi::flex::Series, data);
+
+    fn new(data: &[f64]) -> CSeries {
+        let (layout, offset) = Layout::new::<c_ffi::flex::Series>()
+            .extend(Layout::array::<f64>(data.len()).expect("series too long"))
+            .expect("series too long");
+        assert_eq!(offset, Self::DATA);
+        // SAFETY: the layout has the size of the header, which isn't zero.
+        let raw = unsafe { alloc::alloc(layout) };
+        let Some(ptr) = NonNull::new(raw.cast::<c_ffi::flex::Series>()) else {
+            alloc::handle_alloc_error(layout)
+        };
+        // SAFETY: the allocation has room for the header and `data`.
+        unsafe {
+            ptr::addr_of_mut!((*ptr.as_ptr()).len).write(data.len());
+            ptr::copy_nonoverlapping(data.as_ptr(), raw.add(Self::DATA).cast(), data.len());
+        }
+        CSeries { ptr, layout }
+    }
+
+    // A pointer to the whole allocation, not a reference: a reference would
+    // cover only the header, and C reads the elements past it.
+    fn as_ptr(&self) -> *mut c_ffi::flex::Series {
+        self.ptr.as_ptr()
+    }
+
+    fn data(&self) -> &[f64] {
+        // SAFETY: `new` wrote `len` elements after the header.
+        unsafe {
+            let data = self.as_ptr().cast::<u8>().add(Self::DATA).cast::<f64>();
+            std::slice::from_raw_parts(data, (*self.as_ptr()).len)
+        }
+    }
+}
+
+impl Drop for CSeries {
+    fn drop(&mut self) {
+        // SAFETY: allocated in `new` with this layout.
+        unsafe { alloc::dealloc(self.ptr.as_ptr().cast(), self.layout) }
+    }
+}
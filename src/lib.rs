@@ -1,6 +1,17 @@
+pub mod bindgen_accessors;
+pub mod build_config;
 pub mod c_wrapper;
+pub mod callback_variants;
+pub mod ir_analysis;
+pub mod kernels;
+pub mod overflow_variants;
 pub mod rust_port;
+pub mod toolchain;
+#[cfg(feature = "transpile-inline")]
+pub mod transpiled;
 
 // Re-export for easy access
+pub use build_config::BUILD_CONFIG;
 pub use c_wrapper as c_ffi;
+pub use kernels::{CBackend, Kernels, RustBackend};
 pub use rust_port as native;
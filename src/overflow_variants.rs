@@ -0,0 +1,89 @@
+//! One instance of each integer kernel of the port and of its overflow
+//! variants. They are leaf functions that rustc only emits where they are
+//! inlined, so without these the library IR wouldn't have them.
+//!
+//! Compare them with `ir_report --overflow`, with overflow checks off
+//! (`--release`) and on (`--profile release-overflow-checks`).
+
+use crate::native;
+use crate::native::enums::Op;
+
+#[inline(never)]
+pub fn inc(x: i32) -> i32 {
+    native::input::inc(x)
+}
+
+#[inline(never)]
+pub fn checked_inc(x: i32) -> Option<i32> {
+    native::input::checked_inc(x)
+}
+
+#[inline(never)]
+pub fn wrapping_inc(x: i32) -> i32 {
+    native::input::wrapping_inc(x)
+}
+
+#[inline(never)]
+pub fn saturating_inc(x: i32) -> i32 {
+    native::input::saturating_inc(x)
+}
+
+#[inline(never)]
+pub fn dec(x: i32) -> i32 {
+    native::input::dec(x)
+}
+
+#[inline(never)]
+pub fn checked_dec(x: i32) -> Option<i32> {
+    native::input::checked_dec(x)
+}
+
+#[inline(never)]
+pub fn wrapping_dec(x: i32) -> i32 {
+    native::input::wrapping_dec(x)
+}
+
+#[inline(never)]
+pub fn saturating_dec(x: i32) -> i32 {
+    native::input::saturating_dec(x)
+}
+
+#[inline(never)]
+pub fn factorial(n: i32) -> i32 {
+    native::math_ops::factorial(n)
+}
+
+#[inline(never)]
+pub fn checked_factorial(n: i32) -> Option<i32> {
+    native::math_ops::checked_factorial(n)
+}
+
+#[inline(never)]
+pub fn wrapping_factorial(n: i32) -> i32 {
+    native::math_ops::wrapping_factorial(n)
+}
+
+#[inline(never)]
+pub fn saturating_factorial(n: i32) -> i32 {
+    native::math_ops::saturating_factorial(n)
+}
+
+#[inline(never)]
+pub fn apply_op(op: Op, a: i32, b: i32) -> i32 {
+    native::enums::apply_op(op, a, b)
+}
+
+#[inline(never)]
+pub fn checked_apply_op(op: Op, a: i32, b: i32) -> Option<i32> {
+    native::enums::checked_apply_op(op, a, b)
+}
+
+#[inline(never)]
+pub fn wrapping_apply_op(op: Op, a: i32, b: i32) -> i32 {
+    native::enums::wrapping_apply_op(op, a, b)
+}
+
+#[inline(never)]
+pub fn saturating_apply_op(op: Op, a: i32, b: i32) -> i32 {
+    native::enums::saturating_apply_op(op, a, b)
+}
@@ -1 +1,14 @@
+//! Rust ports of the C kernels, one module per header under `c_src`, with the
+//! same function names and signatures as the safe wrappers in `c_ffi`.
 
+pub mod bitfields;
+pub mod callbacks;
+pub mod enums;
+pub mod flex;
+pub mod input;
+pub mod macros;
+pub mod math_ops;
+#[cfg(target_arch = "x86_64")]
+pub mod simd;
+pub mod structs;
+pub mod unions;
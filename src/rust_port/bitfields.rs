@@ -0,0 +1,45 @@
+//! Port of `c_src/bitfields.h`.
+//!
+//! `Instr` has a plain field for each C bitfield, so reading one is a load
+//! rather than a shift and a mask. The masks move to where the fields are
+//! written.
+
+const OPCODE_MASK: u32 = (1 << 6) - 1;
+const REGISTER_MASK: u32 = (1 << 5) - 1;
+const IMM_MASK: u32 = (1 << 16) - 1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Instr {
+    pub opcode: u8,
+    pub dest: u8,
+    pub src: u8,
+    pub imm: u16,
+}
+
+pub fn instr_encode(opcode: u32, dest: u32, src: u32, imm: u32) -> Instr {
+    Instr {
+        opcode: (opcode & OPCODE_MASK) as u8,
+        dest: (dest & REGISTER_MASK) as u8,
+        src: (src & REGISTER_MASK) as u8,
+        imm: (imm & IMM_MASK) as u16,
+    }
+}
+
+pub fn instr_opcode(instr: Instr) -> u32 {
+    instr.opcode.into()
+}
+
+pub fn instr_imm(instr: Instr) -> u32 {
+    instr.imm.into()
+}
+
+pub fn instr_set_dest(instr: &mut Instr, dest: u32) {
+    instr.dest = (dest & REGISTER_MASK) as u8;
+}
+
+/// Sums the `imm` fields of `instrs`, wrapping around.
+pub fn instr_sum_imm(instrs: &[Instr]) -> u32 {
+    instrs
+        .iter()
+        .fold(0, |sum: u32, instr| sum.wrapping_add(instr.imm.into()))
+}
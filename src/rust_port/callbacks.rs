@@ -0,0 +1,9 @@
+//! Port of `c_src/callbacks.h`.
+
+pub fn sort_ints(values: &mut [i32], mut compare: impl FnMut(&i32, &i32) -> i32) {
+    values.sort_by(|a, b| compare(a, b).cmp(&0));
+}
+
+pub fn map_array(values: &mut [i32], mut f: impl FnMut(i32) -> i32) {
+    values.iter_mut().for_each(|value| *value = f(*value));
+}
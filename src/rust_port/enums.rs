@@ -0,0 +1,113 @@
+//! Port of `c_src/enums.h`.
+
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Circle = 0,
+    Square = 1,
+    Triangle = 2,
+    Hexagon = 3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Add = 1,
+    Sub = 2,
+    Mul = 4,
+    Div = 8,
+    Neg = 16,
+}
+
+pub fn shape_sides(shape: Shape) -> i32 {
+    match shape {
+        Shape::Circle => 0,
+        Shape::Square => 4,
+        Shape::Triangle => 3,
+        Shape::Hexagon => 6,
+    }
+}
+
+pub fn shape_area(shape: Shape, size: f64) -> f64 {
+    match shape {
+        Shape::Circle => PI * size * size,
+        Shape::Square => size * size,
+        Shape::Triangle => 0.4330127018922193 * size * size,
+        Shape::Hexagon => 2.598076211353316 * size * size,
+    }
+}
+
+pub fn shape_next(shape: Shape) -> Shape {
+    match shape {
+        Shape::Circle => Shape::Square,
+        Shape::Square => Shape::Triangle,
+        Shape::Triangle => Shape::Hexagon,
+        Shape::Hexagon => Shape::Circle,
+    }
+}
+
+pub fn apply_op(op: Op, a: i32, b: i32) -> i32 {
+    match op {
+        Op::Add => a + b,
+        Op::Sub => a - b,
+        Op::Mul => a * b,
+        Op::Div => {
+            if b != 0 {
+                a / b
+            } else {
+                0
+            }
+        }
+        Op::Neg => -a,
+    }
+}
+
+/// Returns `None` if the operation overflows. Dividing by zero still gives
+/// `Some(0)`.
+pub fn checked_apply_op(op: Op, a: i32, b: i32) -> Option<i32> {
+    match op {
+        Op::Add => a.checked_add(b),
+        Op::Sub => a.checked_sub(b),
+        Op::Mul => a.checked_mul(b),
+        Op::Div => {
+            if b != 0 {
+                a.checked_div(b)
+            } else {
+                Some(0)
+            }
+        }
+        Op::Neg => a.checked_neg(),
+    }
+}
+
+pub fn wrapping_apply_op(op: Op, a: i32, b: i32) -> i32 {
+    match op {
+        Op::Add => a.wrapping_add(b),
+        Op::Sub => a.wrapping_sub(b),
+        Op::Mul => a.wrapping_mul(b),
+        Op::Div => {
+            if b != 0 {
+                a.wrapping_div(b)
+            } else {
+                0
+            }
+        }
+        Op::Neg => a.wrapping_neg(),
+    }
+}
+
+pub fn saturating_apply_op(op: Op, a: i32, b: i32) -> i32 {
+    match op {
+        Op::Add => a.saturating_add(b),
+        Op::Sub => a.saturating_sub(b),
+        Op::Mul => a.saturating_mul(b),
+        Op::Div => {
+            if b != 0 {
+                a.saturating_div(b)
+            } else {
+                0
+            }
+        }
+        Op::Neg => a.saturating_neg(),
+    }
+}
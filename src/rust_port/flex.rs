@@ -0,0 +1,18 @@
+//! Port of `c_src/flex.h`.
+//!
+//! A flexible array member is a length with the elements after it, which in
+//! Rust is a `Vec`. The elements are in their own allocation, so reaching
+//! them takes one more load than in C.
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Series {
+    pub data: Vec<f64>,
+}
+
+pub fn series_sum(s: &Series) -> f64 {
+    s.data.iter().sum()
+}
+
+pub fn series_scale(s: &mut Series, k: f64) {
+    s.data.iter_mut().for_each(|x| *x *= k);
+}
@@ -0,0 +1,39 @@
+//! Port of `c_src/input.h`.
+//!
+//! `inc` and `dec` panic on overflow with overflow checks on, and wrap with
+//! them off; in C, overflow is undefined behavior. The variants behave the
+//! same either way.
+
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+pub fn dec(x: i32) -> i32 {
+    x - 1
+}
+
+/// Returns `None` if `x + 1` overflows.
+pub fn checked_inc(x: i32) -> Option<i32> {
+    x.checked_add(1)
+}
+
+pub fn wrapping_inc(x: i32) -> i32 {
+    x.wrapping_add(1)
+}
+
+pub fn saturating_inc(x: i32) -> i32 {
+    x.saturating_add(1)
+}
+
+/// Returns `None` if `x - 1` overflows.
+pub fn checked_dec(x: i32) -> Option<i32> {
+    x.checked_sub(1)
+}
+
+pub fn wrapping_dec(x: i32) -> i32 {
+    x.wrapping_sub(1)
+}
+
+pub fn saturating_dec(x: i32) -> i32 {
+    x.saturating_sub(1)
+}
@@ -0,0 +1,66 @@
+//! Port of `c_src/macros.h`.
+//!
+//! The `#define`s are used through the constants bindgen bound them as, so
+//! the ports fold the same values the C functions do. The function-like
+//! macros are ported as the functions `build.rs` writes out from them.
+//!
+//! `buffer_bytes` and `scaled_buffer_bytes` are `#[inline(never)]`: rustc
+//! only emits small leaf functions where they are inlined, so without it
+//! the library IR wouldn't show whether they fold to a constant.
+
+use crate::c_ffi::macros::{
+    BUFFER_BYTES, BUFFER_LEN, COLOR_BLUE, COLOR_GREEN, COLOR_RED, ELEM_SIZE, FLAG_MASK, MAX_TEMP,
+    MIN_TEMP, SCALE,
+};
+
+pub fn clamp_int(x: i32, lo: i32, hi: i32) -> i32 {
+    // Not `x.clamp(lo, hi)`, which panics if `lo > hi`.
+    if x < lo {
+        lo
+    } else if x > hi {
+        hi
+    } else {
+        x
+    }
+}
+
+pub fn in_range(x: i32, lo: i32, hi: i32) -> i32 {
+    i32::from(lo <= x && x <= hi)
+}
+
+#[inline(never)]
+pub fn buffer_bytes() -> i32 {
+    BUFFER_BYTES
+}
+
+#[inline(never)]
+pub fn scaled_buffer_bytes() -> f64 {
+    f64::from(BUFFER_BYTES) * SCALE
+}
+
+pub fn set_flag(flags: u32) -> u32 {
+    flags | FLAG_MASK as u32
+}
+
+/// The next of the three colors, or -1 for a value that is no color.
+pub fn next_color(color: i32) -> i32 {
+    match color {
+        COLOR_RED => COLOR_GREEN,
+        COLOR_GREEN => COLOR_BLUE,
+        COLOR_BLUE => COLOR_RED,
+        _ => -1,
+    }
+}
+
+pub fn clamp(x: i32, lo: i32, hi: i32) -> i32 {
+    clamp_int(x, lo, hi)
+}
+
+pub fn clamp_temp(t: i32) -> i32 {
+    clamp_int(t, MIN_TEMP, MAX_TEMP)
+}
+
+/// The byte offset of element `i` in the ring buffer.
+pub fn buffer_offset(i: usize) -> usize {
+    (i % BUFFER_LEN as usize) * ELEM_SIZE as usize
+}
@@ -0,0 +1,60 @@
+//! Port of `c_src/math_ops.h`.
+
+pub fn factorial(n: i32) -> i32 {
+    if n <= 1 {
+        return 1;
+    }
+    let mut result = 1;
+    for i in 2..=n {
+        result *= i;
+    }
+    result
+}
+
+/// Returns `None` if `n!` overflows.
+pub fn checked_factorial(n: i32) -> Option<i32> {
+    (2..=n).try_fold(1i32, |product, i| product.checked_mul(i))
+}
+
+pub fn wrapping_factorial(n: i32) -> i32 {
+    (2..=n).fold(1i32, |product, i| product.wrapping_mul(i))
+}
+
+pub fn saturating_factorial(n: i32) -> i32 {
+    (2..=n).fold(1i32, |product, i| product.saturating_mul(i))
+}
+
+pub fn vector_dot_product(a: &[f64], b: &[f64]) -> f64 {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+pub fn is_prime(n: u32) -> bool {
+    if n <= 1 {
+        return false;
+    }
+    if n <= 3 {
+        return true;
+    }
+    if n.is_multiple_of(2) || n.is_multiple_of(3) {
+        return false;
+    }
+
+    let mut i = 5;
+    while i <= n / i {
+        if n.is_multiple_of(i) || n.is_multiple_of(i + 2) {
+            return false;
+        }
+        i += 6;
+    }
+    true
+}
+
+pub fn matrix_multiply_2x2(a: &[f64; 4], b: &[f64; 4]) -> [f64; 4] {
+    [
+        a[0] * b[0] + a[1] * b[2],
+        a[0] * b[1] + a[1] * b[3],
+        a[2] * b[0] + a[3] * b[2],
+        a[2] * b[1] + a[3] * b[3],
+    ]
+}
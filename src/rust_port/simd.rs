@@ -0,0 +1,221 @@
+//! The SIMD kernels in three flavours:
+//!
+//! - `_sse2` and `_avx2`: `std::arch` intrinsics, step for step like the C
+//!   functions, so they give the same results bit for bit.
+//! - `_iter`: plain iterators, left to the autovectorizer.
+//! - `_unrolled`: loops over fixed-size chunks, unrolled by hand into
+//!   independent lanes.
+//!
+//! SSE2 is part of x86-64, so the `_sse2` functions run anywhere, and only
+//! the `unsafe` blocks around their intrinsics need to say so. The
+//! `_avx2` functions are compiled for AVX2 with `#[target_feature]`, and
+//! calling one needs a check that the CPU has it, e.g.
+//! `is_x86_feature_detected!("avx2")`.
+//!
+//! LLVM won't reorder float additions, so `sum_iter` adds one element at a
+//! time, while `sum_unrolled` keeps 8 sums that it can put in a vector; it
+//! adds in the same order as `sum_avx2`.
+
+use std::arch::x86_64::*;
+
+pub fn saxpy_sse2(a: f32, x: &[f32], y: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    // SAFETY: x86-64 has SSE2.
+    let va = unsafe { _mm_set1_ps(a) };
+    let mut y_chunks = y.chunks_exact_mut(4);
+    for (y, x) in (&mut y_chunks).zip(x.chunks_exact(4)) {
+        // SAFETY: both chunks have 4 elements, and x86-64 has SSE2.
+        unsafe {
+            let vx = _mm_loadu_ps(x.as_ptr());
+            let vy = _mm_loadu_ps(y.as_ptr());
+            _mm_storeu_ps(y.as_mut_ptr(), _mm_add_ps(_mm_mul_ps(va, vx), vy));
+        }
+    }
+    let x_rest = &x[x.len() / 4 * 4..];
+    for (y, &x) in y_chunks.into_remainder().iter_mut().zip(x_rest) {
+        *y += a * x;
+    }
+}
+
+/// # Safety
+///
+/// The CPU must have AVX2.
+#[target_feature(enable = "avx2")]
+pub fn saxpy_avx2(a: f32, x: &[f32], y: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    let va = _mm256_set1_ps(a);
+    let mut y_chunks = y.chunks_exact_mut(8);
+    for (y, x) in (&mut y_chunks).zip(x.chunks_exact(8)) {
+        // SAFETY: both chunks have 8 elements.
+        unsafe {
+            let vx = _mm256_loadu_ps(x.as_ptr());
+            let vy = _mm256_loadu_ps(y.as_ptr());
+            _mm256_storeu_ps(y.as_mut_ptr(), _mm256_add_ps(_mm256_mul_ps(va, vx), vy));
+        }
+    }
+    let x_rest = &x[x.len() / 8 * 8..];
+    for (y, &x) in y_chunks.into_remainder().iter_mut().zip(x_rest) {
+        *y += a * x;
+    }
+}
+
+pub fn saxpy_iter(a: f32, x: &[f32], y: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    y.iter_mut().zip(x).for_each(|(y, &x)| *y += a * x);
+}
+
+pub fn saxpy_unrolled(a: f32, x: &[f32], y: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    let mut y_chunks = y.chunks_exact_mut(8);
+    for (y, x) in (&mut y_chunks).zip(x.chunks_exact(8)) {
+        for i in 0..8 {
+            y[i] += a * x[i];
+        }
+    }
+    let x_rest = &x[x.len() / 8 * 8..];
+    for (y, &x) in y_chunks.into_remainder().iter_mut().zip(x_rest) {
+        *y += a * x;
+    }
+}
+
+pub fn sum_sse2(x: &[f32]) -> f32 {
+    let chunks = x.chunks_exact(4);
+    let rest = chunks.remainder();
+    // SAFETY: x86-64 has SSE2.
+    let mut acc = unsafe { _mm_setzero_ps() };
+    for chunk in chunks {
+        // SAFETY: the chunk has 4 elements, and x86-64 has SSE2.
+        acc = unsafe { _mm_add_ps(acc, _mm_loadu_ps(chunk.as_ptr())) };
+    }
+    let mut lanes = [0.0; 4];
+    // SAFETY: `lanes` has 4 elements, and x86-64 has SSE2.
+    unsafe { _mm_storeu_ps(lanes.as_mut_ptr(), acc) };
+    let mut sum = (lanes[0] + lanes[1]) + (lanes[2] + lanes[3]);
+    for &v in rest {
+        sum += v;
+    }
+    sum
+}
+
+/// # Safety
+///
+/// The CPU must have AVX2.
+#[target_feature(enable = "avx2")]
+pub fn sum_avx2(x: &[f32]) -> f32 {
+    let chunks = x.chunks_exact(8);
+    let rest = chunks.remainder();
+    let mut acc = _mm256_setzero_ps();
+    for chunk in chunks {
+        // SAFETY: the chunk has 8 elements.
+        acc = _mm256_add_ps(acc, unsafe { _mm256_loadu_ps(chunk.as_ptr()) });
+    }
+    let mut lanes = [0.0; 8];
+    // SAFETY: `lanes` has 8 elements.
+    unsafe { _mm256_storeu_ps(lanes.as_mut_ptr(), acc) };
+    let mut sum = add_pairwise(lanes);
+    for &v in rest {
+        sum += v;
+    }
+    sum
+}
+
+pub fn sum_iter(x: &[f32]) -> f32 {
+    x.iter().sum()
+}
+
+pub fn sum_unrolled(x: &[f32]) -> f32 {
+    let chunks = x.chunks_exact(8);
+    let rest = chunks.remainder();
+    let mut lanes = [0.0; 8];
+    for chunk in chunks {
+        for i in 0..8 {
+            lanes[i] += chunk[i];
+        }
+    }
+    let mut sum = add_pairwise(lanes);
+    for &v in rest {
+        sum += v;
+    }
+    sum
+}
+
+/// Adds up 8 lanes as `sum_avx2` does.
+fn add_pairwise(l: [f32; 8]) -> f32 {
+    ((l[0] + l[1]) + (l[2] + l[3])) + ((l[4] + l[5]) + (l[6] + l[7]))
+}
+
+pub fn byte_histogram_sse2(data: &[u8]) -> [u32; 256] {
+    let mut tables = [[0; 256]; 4];
+    let chunks = data.chunks_exact(16);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        // SAFETY: the chunk has 16 bytes, and x86-64 has SSE2.
+        let (low, high) = unsafe {
+            let bytes = _mm_loadu_si128(chunk.as_ptr().cast());
+            (
+                _mm_cvtsi128_si64(bytes),
+                _mm_cvtsi128_si64(_mm_unpackhi_epi64(bytes, bytes)),
+            )
+        };
+        count_word(&mut tables, low as u64);
+        count_word(&mut tables, high as u64);
+    }
+    for &b in rest {
+        tables[0][usize::from(b)] += 1;
+    }
+    merge(&tables)
+}
+
+/// # Safety
+///
+/// The CPU must have AVX2.
+#[target_feature(enable = "avx2")]
+pub fn byte_histogram_avx2(data: &[u8]) -> [u32; 256] {
+    let mut tables = [[0; 256]; 4];
+    let chunks = data.chunks_exact(32);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        // SAFETY: the chunk has 32 bytes.
+        let bytes = unsafe { _mm256_loadu_si256(chunk.as_ptr().cast()) };
+        count_word(&mut tables, _mm256_extract_epi64::<0>(bytes) as u64);
+        count_word(&mut tables, _mm256_extract_epi64::<1>(bytes) as u64);
+        count_word(&mut tables, _mm256_extract_epi64::<2>(bytes) as u64);
+        count_word(&mut tables, _mm256_extract_epi64::<3>(bytes) as u64);
+    }
+    for &b in rest {
+        tables[0][usize::from(b)] += 1;
+    }
+    merge(&tables)
+}
+
+pub fn byte_histogram_iter(data: &[u8]) -> [u32; 256] {
+    let mut counts = [0; 256];
+    data.iter().for_each(|&b| counts[usize::from(b)] += 1);
+    counts
+}
+
+pub fn byte_histogram_unrolled(data: &[u8]) -> [u32; 256] {
+    let mut tables = [[0; 256]; 4];
+    let chunks = data.chunks_exact(4);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        for i in 0..4 {
+            tables[i][usize::from(chunk[i])] += 1;
+        }
+    }
+    for &b in rest {
+        tables[0][usize::from(b)] += 1;
+    }
+    merge(&tables)
+}
+
+/// Counts the 8 bytes of `word` into the 4 tables, 2 bytes each.
+fn count_word(tables: &mut [[u32; 256]; 4], word: u64) {
+    for b in 0..8 {
+        tables[b % 4][(word >> (8 * b)) as usize & 0xff] += 1;
+    }
+}
+
+fn merge(tables: &[[u32; 256]; 4]) -> [u32; 256] {
+    std::array::from_fn(|v| tables[0][v] + tables[1][v] + tables[2][v] + tables[3][v])
+}
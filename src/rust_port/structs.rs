@@ -0,0 +1,81 @@
+//! Port of `c_src/structs.h`.
+//!
+//! The structs are `#[repr(C)]` with the fields of their C twins, so they
+//! have the same layout, and only the calling convention around them differs
+//! between `extern "C"` and Rust functions.
+
+use std::array;
+use std::mem::{align_of, offset_of, size_of};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sample {
+    pub id: i32,
+    pub weight: f32,
+    pub value: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Block {
+    pub values: [f64; 8],
+}
+
+// The layouts bindgen checks the C structs against.
+const _: () = {
+    assert!(size_of::<Vec2>() == 8 && align_of::<Vec2>() == 4);
+    assert!(offset_of!(Vec2, x) == 0 && offset_of!(Vec2, y) == 4);
+    assert!(size_of::<Sample>() == 16 && align_of::<Sample>() == 8);
+    assert!(offset_of!(Sample, id) == 0);
+    assert!(offset_of!(Sample, weight) == 4);
+    assert!(offset_of!(Sample, value) == 8);
+    assert!(size_of::<Block>() == 64 && align_of::<Block>() == 8);
+    assert!(offset_of!(Block, values) == 0);
+};
+
+pub fn vec2_add(a: Vec2, b: Vec2) -> Vec2 {
+    Vec2 {
+        x: a.x + b.x,
+        y: a.y + b.y,
+    }
+}
+
+pub fn sample_scale(mut s: Sample, k: f32) -> Sample {
+    s.weight *= k;
+    s.value *= f64::from(k);
+    s
+}
+
+pub fn block_add(a: Block, b: Block) -> Block {
+    Block {
+        values: array::from_fn(|i| a.values[i] + b.values[i]),
+    }
+}
+
+pub fn block_splat(x: f64) -> Block {
+    Block { values: [x; 8] }
+}
+
+pub fn vec2_dot(a: &Vec2, b: &Vec2) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+pub fn sample_scale_in_place(s: &mut Sample, k: f32) {
+    s.weight *= k;
+    s.value *= f64::from(k);
+}
+
+pub fn block_sum(b: &Block) -> f64 {
+    b.values.iter().sum()
+}
+
+pub fn block_add_into(a: &Block, b: &Block) -> Block {
+    block_add(*a, *b)
+}
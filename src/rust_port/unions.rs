@@ -0,0 +1,37 @@
+//! Port of `c_src/unions.h`.
+//!
+//! The tagged union becomes an enum. Rust picks its layout, so unlike the
+//! structs in `structs` it can't be passed to C as it is; the C backend
+//! converts it.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Pair(i32, i32),
+}
+
+pub fn value_int(i: i64) -> Value {
+    Value::Int(i)
+}
+
+pub fn value_float(f: f64) -> Value {
+    Value::Float(f)
+}
+
+pub fn value_pair(a: i32, b: i32) -> Value {
+    Value::Pair(a, b)
+}
+
+/// There is no unknown kind to handle: the match is exhaustive.
+pub fn value_to_double(v: Value) -> f64 {
+    match v {
+        Value::Int(i) => i as f64,
+        Value::Float(f) => f,
+        Value::Pair(a, b) => f64::from(a) + f64::from(b),
+    }
+}
+
+pub fn value_sum(values: &[Value]) -> f64 {
+    values.iter().map(|&v| value_to_double(v)).sum()
+}
@@ -0,0 +1,286 @@
+//! Finds the clang/LLVM tools that match the LLVM version rustc was built
+//! with.
+//!
+//! Cross-language LTO and IR post-processing only work when every tool reads
+//! and writes the same bitcode format as rustc. A mismatched tool often
+//! fails without an error: the linker skips bitcode it can't read, and
+//! `llvm-dis` rejects newer bitcode. So each tool is checked against
+//! `rustc -vV` before it is used.
+//!
+//! This module only depends on `std`, because `build.rs` includes it too.
+
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// An LLVM tool this crate drives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Clang,
+    LlvmAr,
+    LlvmDis,
+    LlvmLink,
+    Opt,
+    LlvmObjdump,
+    Lld,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 7] = [
+        Tool::Clang,
+        Tool::LlvmAr,
+        Tool::LlvmDis,
+        Tool::LlvmLink,
+        Tool::Opt,
+        Tool::LlvmObjdump,
+        Tool::Lld,
+    ];
+
+    /// The unversioned binary name, e.g. `llvm-dis`.
+    pub fn binary(self) -> &'static str {
+        match self {
+            Tool::Clang => "clang",
+            Tool::LlvmAr => "llvm-ar",
+            Tool::LlvmDis => "llvm-dis",
+            Tool::LlvmLink => "llvm-link",
+            Tool::Opt => "opt",
+            Tool::LlvmObjdump => "llvm-objdump",
+            Tool::Lld => "ld.lld",
+        }
+    }
+
+    /// The environment variable that overrides the search, e.g. `IR_LLVM_DIS`.
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Tool::Clang => "IR_CLANG",
+            Tool::LlvmAr => "IR_LLVM_AR",
+            Tool::LlvmDis => "IR_LLVM_DIS",
+            Tool::LlvmLink => "IR_LLVM_LINK",
+            Tool::Opt => "IR_OPT",
+            Tool::LlvmObjdump => "IR_LLVM_OBJDUMP",
+            Tool::Lld => "IR_LLD",
+        }
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.binary())
+    }
+}
+
+/// A `major.minor.patch` LLVM version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LlvmVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl LlvmVersion {
+    /// Parses the leading `major[.minor[.patch]]` of `version`, ignoring
+    /// vendor suffixes such as `-1ubuntu1` or `git`.
+    pub fn parse(version: &str) -> Option<LlvmVersion> {
+        let mut parts = version.split('.').map(|part| {
+            let digits = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            part[..digits].parse::<u32>().ok()
+        });
+        let major = parts.next()??;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(LlvmVersion {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// Finds the version in the `--version` output of clang or an LLVM tool:
+    /// the first dotted number on the line that names the version, or after
+    /// `LLD ` for lld, which vendors print as e.g. `Debian LLD 14.0.6`.
+    pub fn from_version_output(output: &str) -> Option<LlvmVersion> {
+        output
+            .lines()
+            .filter_map(|line| match line.find("LLD ") {
+                Some(start) => Some(&line[start + "LLD ".len()..]),
+                None => line.contains("version").then_some(line),
+            })
+            .flat_map(str::split_whitespace)
+            .find(|word| word.starts_with(|c: char| c.is_ascii_digit()) && word.contains('.'))
+            .and_then(LlvmVersion::parse)
+    }
+}
+
+impl fmt::Display for LlvmVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Why a tool could not be used.
+#[derive(Debug)]
+pub enum ToolchainError {
+    /// `rustc -vV` could not be run or did not report an LLVM version.
+    RustcVersion { rustc: String, reason: String },
+    /// No candidate for `tool` was built against rustc's LLVM major version.
+    /// Lists every candidate that was tried, with the version it reported.
+    NoMatchingTool {
+        tool: Tool,
+        llvm: LlvmVersion,
+        tried: Vec<(String, Option<LlvmVersion>)>,
+    },
+    /// The tool set through its environment variable has the wrong version.
+    OverrideMismatch {
+        tool: Tool,
+        path: String,
+        found: Option<LlvmVersion>,
+        llvm: LlvmVersion,
+    },
+}
+
+impl fmt::Display for ToolchainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolchainError::RustcVersion { rustc, reason } => {
+                write!(f, "couldn't read the LLVM version of `{rustc}`: {reason}")
+            }
+            ToolchainError::NoMatchingTool { tool, llvm, tried } => {
+                write!(
+                    f,
+                    "no `{tool}` for LLVM {} (rustc uses LLVM {llvm}); tried",
+                    llvm.major
+                )?;
+                for (i, (candidate, version)) in tried.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    match version {
+                        Some(version) => write!(f, "{sep}`{candidate}` (LLVM {version})")?,
+                        None => write!(f, "{sep}`{candidate}` (not found)")?,
+                    }
+                }
+                write!(
+                    f,
+                    ". Install `{tool}-{}` or point {} at a matching binary",
+                    llvm.major,
+                    tool.env_var()
+                )
+            }
+            ToolchainError::OverrideMismatch {
+                tool,
+                path,
+                found,
+                llvm,
+            } => {
+                let found = match found {
+                    Some(version) => format!("LLVM {version}"),
+                    None => "no version".to_owned(),
+                };
+                write!(
+                    f,
+                    "{}=`{path}` reports {found}, but rustc uses LLVM {llvm}; \
+                     `{tool}` must have the same major version",
+                    tool.env_var()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ToolchainError {}
+
+/// The LLVM version of the active rustc, used to pick every other tool.
+#[derive(Clone, Debug)]
+pub struct Toolchain {
+    pub rustc: String,
+    /// The first line of `rustc -vV`, e.g. `rustc 1.90.0 (1159e78c4 2025-09-14)`.
+    pub rustc_version: String,
+    pub llvm: LlvmVersion,
+}
+
+impl Toolchain {
+    /// Reads the LLVM version of `$RUSTC`, or of `rustc` on the `PATH`.
+    /// Cargo sets `RUSTC` for build scripts.
+    pub fn from_env() -> Result<Toolchain, ToolchainError> {
+        let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+        Toolchain::from_rustc(&rustc)
+    }
+
+    /// Reads the LLVM version from `rustc -vV`.
+    pub fn from_rustc(rustc: &str) -> Result<Toolchain, ToolchainError> {
+        let error = |reason: String| ToolchainError::RustcVersion {
+            rustc: rustc.to_owned(),
+            reason,
+        };
+        let output = Command::new(rustc)
+            .arg("-vV")
+            .output()
+            .map_err(|err| error(err.to_string()))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let llvm = parse_rustc_llvm_version(&stdout)
+            .ok_or_else(|| error("no `LLVM version:` line in `rustc -vV`".to_owned()))?;
+        Ok(Toolchain {
+            rustc: rustc.to_owned(),
+            rustc_version: stdout.lines().next().unwrap_or_default().to_owned(),
+            llvm,
+        })
+    }
+
+    /// Finds `tool` built against the same LLVM major version as rustc.
+    ///
+    /// The tool's environment variable wins if it is set. Otherwise this
+    /// tries `<tool>-<major>`, `<tool>` and `/usr/lib/llvm-<major>/bin/<tool>`
+    /// in that order, and takes the first one with a matching version.
+    pub fn find(&self, tool: Tool) -> Result<PathBuf, ToolchainError> {
+        if let Ok(path) = env::var(tool.env_var()) {
+            let found = tool_version(&path);
+            return match found {
+                Some(version) if version.major == self.llvm.major => Ok(PathBuf::from(path)),
+                _ => Err(ToolchainError::OverrideMismatch {
+                    tool,
+                    path,
+                    found,
+                    llvm: self.llvm,
+                }),
+            };
+        }
+
+        let mut tried = Vec::new();
+        for candidate in self.candidates(tool) {
+            let version = tool_version(&candidate);
+            if version.is_some_and(|version| version.major == self.llvm.major) {
+                return Ok(PathBuf::from(candidate));
+            }
+            tried.push((candidate, version));
+        }
+        Err(ToolchainError::NoMatchingTool {
+            tool,
+            llvm: self.llvm,
+            tried,
+        })
+    }
+
+    fn candidates(&self, tool: Tool) -> Vec<String> {
+        let major = self.llvm.major;
+        vec![
+            format!("{}-{major}", tool.binary()),
+            tool.binary().to_owned(),
+            format!("/usr/lib/llvm-{major}/bin/{}", tool.binary()),
+        ]
+    }
+}
+
+/// Extracts the LLVM version from the output of `rustc -vV`.
+pub fn parse_rustc_llvm_version(output: &str) -> Option<LlvmVersion> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("LLVM version:"))
+        .and_then(|version| LlvmVersion::parse(version.trim()))
+}
+
+/// Runs `<program> --version`, or returns `None` if it can't be run.
+pub fn tool_version(program: &str) -> Option<LlvmVersion> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    LlvmVersion::from_version_output(&String::from_utf8_lossy(&output.stdout))
+}
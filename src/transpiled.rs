@@ -0,0 +1,11 @@
+//! The `static inline` C functions `build.rs` could translate to Rust, one
+//! module per header under `c_src`. See `build/transpile.rs` for what it
+//! translates and how.
+
+// The translation keeps C's structure: explicit returns, casts and
+// parentheses around every operation.
+#![allow(unused_parens)]
+#![allow(clippy::needless_return)]
+#![allow(clippy::unnecessary_cast)]
+
+include!(concat!(env!("OUT_DIR"), "/transpiled.rs"));
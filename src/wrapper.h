@@ -1,6 +0,0 @@
-#ifndef WRAPPER_H
-#define WRAPPER_H
-
-#include "../c_src/include.h"
-
-#endif
\ No newline at end of file
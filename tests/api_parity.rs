@@ -0,0 +1,273 @@
+//! Checks that every C function in `c_ffi` has a port in `native` with a
+//! compatible signature.
+//!
+//! Each binding module `c_ffi::<module>` must have a matching
+//! `native::<module>`, and each function in it a public port of the same
+//! name. The port is compared with the safe wrapper `<fn>_safe` if there is
+//! one, and with the raw binding otherwise. C type aliases such as `c_int`
+//! compare equal to the Rust type they stand for, and paths are compared by
+//! their last segment, so `crate::c_ffi::shapes::Point` matches a native
+//! `Point`. A raw pointer, left in a binding that no safe wrapper can cover,
+//! compares equal to a reference. A header generated in extra enum styles is
+//! checked through its first module only.
+
+use std::any::type_name;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::os::raw;
+use std::path::{Path, PathBuf};
+
+use quote::ToTokens;
+use syn::visit_mut::VisitMut;
+use syn::{FnArg, ForeignItem, Item, ReturnType, Signature, Type, Visibility};
+
+#[cfg(not(feature = "pregenerated-bindings"))]
+const BINDINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(feature = "pregenerated-bindings")]
+const BINDINGS: &str = include_str!("../src/bindings_pregenerated.rs");
+
+/// The functions of one module, by name.
+type Functions = BTreeMap<String, (Signature, bool)>;
+
+#[test]
+fn every_c_function_has_a_matching_port() {
+    let native = native_modules();
+    let mut problems = Vec::new();
+    for (module, functions) in c_modules() {
+        let Some(ports) = native.get(&module) else {
+            problems.push(format!("{module}: no `native::{module}` module"));
+            continue;
+        };
+        for (name, c_sig) in functions {
+            match ports.get(&name) {
+                None => problems.push(format!("{module}::{name}: missing in native")),
+                Some((_, false)) => {
+                    problems.push(format!("{module}::{name}: not public in native"))
+                }
+                Some((port, true)) => {
+                    for mismatch in compare(&c_sig, port) {
+                        problems.push(format!("{module}::{name}: {mismatch}"));
+                    }
+                }
+            }
+        }
+    }
+    assert!(
+        problems.is_empty(),
+        "c_ffi and native differ:\n  {}",
+        problems.join("\n  ")
+    );
+}
+
+/// The signature each C function should have in `native`, per module.
+fn c_modules() -> BTreeMap<String, BTreeMap<String, Signature>> {
+    let file = syn::parse_file(BINDINGS).expect("couldn't parse the bindings");
+    let mut modules = BTreeMap::new();
+    let mut headers = BTreeSet::new();
+    for item in file.items {
+        let Item::Mod(module) = item else { continue };
+        if !headers.insert(header(&module.attrs)) {
+            continue;
+        }
+        let module_name = module.ident.to_string();
+        let safe = safe_wrappers(&module_name);
+        let mut functions = BTreeMap::new();
+        for item in module.content.map(|(_, items)| items).unwrap_or_default() {
+            let Item::ForeignMod(foreign) = item else {
+                continue;
+            };
+            for item in foreign.items {
+                let ForeignItem::Fn(func) = item else {
+                    continue;
+                };
+                let name = func.sig.ident.to_string();
+                let sig = match safe.get(&format!("{name}_safe")) {
+                    Some((sig, _)) => sig.clone(),
+                    None => func.sig,
+                };
+                functions.insert(name, sig);
+            }
+        }
+        modules.insert(module_name, functions);
+    }
+    modules
+}
+
+/// The header a binding module was generated from, from its doc comment:
+/// "Bindings for `c_src/<header>`".
+fn header(attrs: &[syn::Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .find_map(|attr| {
+            let doc = attr.meta.require_name_value().ok()?.value.to_token_stream();
+            Some(doc.to_string().split('`').nth(1)?.to_owned())
+        })
+        .unwrap_or_default()
+}
+
+/// The generated safe wrappers of a binding module.
+fn safe_wrappers(module: &str) -> Functions {
+    let path = Path::new(env!("OUT_DIR"))
+        .join("safe_wrappers")
+        .join(format!("{module}.rs"));
+    functions(&parse(&path).items)
+}
+
+/// The functions of every module under `native`, parsed from the sources.
+fn native_modules() -> BTreeMap<String, Functions> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut modules = BTreeMap::new();
+    for item in parse(&root.join("rust_port.rs")).items {
+        let Item::Mod(module) = item else { continue };
+        let name = module.ident.to_string();
+        let items = match module.content {
+            Some((_, items)) => items,
+            None => parse(&module_file(&root.join("rust_port"), &name)).items,
+        };
+        modules.insert(name, functions(&items));
+    }
+    modules
+}
+
+/// Finds the file of an out-of-line `mod name;` in `rust_port.rs`.
+fn module_file(dir: &Path, name: &str) -> PathBuf {
+    let file = dir.join(format!("{name}.rs"));
+    if file.exists() {
+        file
+    } else {
+        dir.join(name).join("mod.rs")
+    }
+}
+
+fn functions(items: &[Item]) -> Functions {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(func) => Some((
+                func.sig.ident.to_string(),
+                (func.sig.clone(), matches!(func.vis, Visibility::Public(_))),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse(path: &Path) -> syn::File {
+    let text = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("couldn't read {}: {err}", path.display()));
+    syn::parse_file(&text).unwrap_or_else(|err| panic!("couldn't parse {}: {err}", path.display()))
+}
+
+/// Lists the differences between the C signature and the port.
+fn compare(c_sig: &Signature, port: &Signature) -> Vec<String> {
+    let c_args = arg_types(c_sig);
+    let port_args = arg_types(port);
+    let mut mismatches = Vec::new();
+    if c_args.len() != port_args.len() {
+        mismatches.push(format!(
+            "takes {} arguments in native, {} in c_ffi",
+            port_args.len(),
+            c_args.len()
+        ));
+    } else {
+        for (i, (c_arg, port_arg)) in c_args.iter().zip(&port_args).enumerate() {
+            if c_arg != port_arg {
+                mismatches.push(format!(
+                    "argument {} is `{port_arg}` in native, `{c_arg}` in c_ffi",
+                    i + 1
+                ));
+            }
+        }
+    }
+    let (c_ret, port_ret) = (return_type(c_sig), return_type(port));
+    if c_ret != port_ret {
+        mismatches.push(format!(
+            "returns `{port_ret}` in native, `{c_ret}` in c_ffi"
+        ));
+    }
+    mismatches
+}
+
+fn arg_types(sig: &Signature) -> Vec<String> {
+    sig.inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(arg) => normalize(&arg.ty),
+            FnArg::Receiver(_) => "self".to_owned(),
+        })
+        .collect()
+}
+
+fn return_type(sig: &Signature) -> String {
+    match &sig.output {
+        ReturnType::Default => "()".to_owned(),
+        ReturnType::Type(_, ty) => normalize(ty),
+    }
+}
+
+/// Spells a type the same way on both sides.
+fn normalize(ty: &Type) -> String {
+    let mut ty = ty.clone();
+    Normalize.visit_type_mut(&mut ty);
+    ty.to_token_stream().to_string()
+}
+
+struct Normalize;
+
+impl VisitMut for Normalize {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        syn::visit_mut::visit_type_mut(self, ty);
+        if let Type::Ptr(ptr) = ty {
+            *ty = Type::Reference(syn::TypeReference {
+                and_token: Default::default(),
+                lifetime: None,
+                mutability: ptr.mutability,
+                elem: ptr.elem.clone(),
+            });
+            return;
+        }
+        let Type::Path(path) = ty else { return };
+        if path.qself.is_some() {
+            return;
+        }
+        let last = path.path.segments.pop().unwrap().into_value();
+        let name = last.ident.to_string();
+        path.path.leading_colon = None;
+        path.path.segments.clear();
+        match c_alias(&name) {
+            Some(alias) => path.path.segments.push(syn::parse_str(alias).unwrap()),
+            None => path.path.segments.push(last),
+        }
+    }
+
+    /// Drops the trailing comma that a formatter leaves in a long
+    /// `FnMut(..)` bound.
+    fn visit_parenthesized_generic_arguments_mut(
+        &mut self,
+        args: &mut syn::ParenthesizedGenericArguments,
+    ) {
+        syn::visit_mut::visit_parenthesized_generic_arguments_mut(self, args);
+        args.inputs.pop_punct();
+    }
+}
+
+/// The Rust type a C type alias stands for on this target.
+fn c_alias(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "c_char" => type_name::<raw::c_char>(),
+        "c_schar" => type_name::<raw::c_schar>(),
+        "c_uchar" => type_name::<raw::c_uchar>(),
+        "c_short" => type_name::<raw::c_short>(),
+        "c_ushort" => type_name::<raw::c_ushort>(),
+        "c_int" => type_name::<raw::c_int>(),
+        "c_uint" => type_name::<raw::c_uint>(),
+        "c_long" => type_name::<raw::c_long>(),
+        "c_ulong" => type_name::<raw::c_ulong>(),
+        "c_longlong" => type_name::<raw::c_longlong>(),
+        "c_ulonglong" => type_name::<raw::c_ulonglong>(),
+        "c_float" => type_name::<raw::c_float>(),
+        "c_double" => type_name::<raw::c_double>(),
+        _ => return None,
+    })
+}
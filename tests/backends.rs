@@ -0,0 +1,782 @@
+//! Runs the same checks against every backend, and checks that the backends
+//! agree with each other.
+
+use ir_comparison::kernels::BACKENDS;
+use ir_comparison::native::bitfields::Instr;
+use ir_comparison::native::enums::{Op, Shape};
+use ir_comparison::native::flex::Series;
+use ir_comparison::native::structs::{Block, Sample, Vec2};
+use ir_comparison::native::unions::Value;
+use ir_comparison::{CBackend, Kernels, RustBackend};
+
+const INPUTS: std::ops::Range<i32> = -1000..1000;
+const SHAPES: [Shape; 4] = [
+    Shape::Circle,
+    Shape::Square,
+    Shape::Triangle,
+    Shape::Hexagon,
+];
+const OPS: [Op; 5] = [Op::Add, Op::Sub, Op::Mul, Op::Div, Op::Neg];
+
+/// Runs the checks of `check`, generic over `K: Kernels`, as the tests
+/// `name::c` and `name::rust`.
+macro_rules! backend_tests {
+    ($name:ident, $check:ident) => {
+        mod $name {
+            use super::*;
+
+            #[test]
+            fn c() {
+                $check(&CBackend);
+            }
+
+            #[test]
+            fn rust() {
+                $check(&RustBackend);
+            }
+        }
+    };
+}
+
+fn inc_and_dec_are_inverses<K: Kernels>(kernels: &K) {
+    for x in INPUTS {
+        assert_eq!(
+            kernels.dec(kernels.inc(x)),
+            x,
+            "{}: dec(inc({x}))",
+            kernels.name()
+        );
+        assert_eq!(
+            kernels.inc(kernels.dec(x)),
+            x,
+            "{}: inc(dec({x}))",
+            kernels.name()
+        );
+    }
+}
+
+backend_tests!(inc_and_dec, inc_and_dec_are_inverses);
+
+fn math_ops_give_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    assert_eq!(kernels.factorial(0), 1, "{name}: factorial(0)");
+    assert_eq!(kernels.factorial(5), 120, "{name}: factorial(5)");
+    assert_eq!(kernels.factorial(12), 479_001_600, "{name}: factorial(12)");
+    assert_eq!(
+        kernels.dot(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]),
+        32.0,
+        "{name}: dot"
+    );
+    assert_eq!(kernels.dot(&[], &[]), 0.0, "{name}: empty dot");
+    let primes: Vec<u32> = (0..30).filter(|&n| kernels.is_prime(n)).collect();
+    assert_eq!(
+        primes,
+        [2, 3, 5, 7, 11, 13, 17, 19, 23, 29],
+        "{name}: is_prime"
+    );
+    // The largest u32 prime, and the product of the primes around 2^16.
+    assert!(
+        kernels.is_prime(4_294_967_291),
+        "{name}: is_prime(2^32 - 5)"
+    );
+    assert!(
+        !kernels.is_prime(65_521 * 65_537),
+        "{name}: is_prime(65521 * 65537)"
+    );
+    let m = [1.0, 2.0, 3.0, 4.0];
+    let identity = [1.0, 0.0, 0.0, 1.0];
+    assert_eq!(
+        kernels.matrix_multiply_2x2(&identity, &m),
+        m,
+        "{name}: I * m"
+    );
+    assert_eq!(
+        kernels.matrix_multiply_2x2(&m, &m),
+        [7.0, 10.0, 15.0, 22.0],
+        "{name}: m * m"
+    );
+}
+
+backend_tests!(math_ops, math_ops_give_known_results);
+
+fn structs_give_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    let (a, b) = (Vec2 { x: 1.0, y: 2.0 }, Vec2 { x: 3.0, y: -4.0 });
+    assert_eq!(
+        kernels.vec2_add(a, b),
+        Vec2 { x: 4.0, y: -2.0 },
+        "{name}: vec2_add"
+    );
+    assert_eq!(kernels.vec2_dot(&a, &b), -5.0, "{name}: vec2_dot");
+
+    let mut s = Sample {
+        id: 7,
+        weight: 0.5,
+        value: 3.0,
+    };
+    let scaled = Sample {
+        id: 7,
+        weight: 1.0,
+        value: 6.0,
+    };
+    assert_eq!(kernels.sample_scale(s, 2.0), scaled, "{name}: sample_scale");
+    kernels.sample_scale_in_place(&mut s, 2.0);
+    assert_eq!(s, scaled, "{name}: sample_scale_in_place");
+
+    let ramp = Block {
+        values: [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0],
+    };
+    let ones = kernels.block_splat(1.0);
+    assert_eq!(ones, Block { values: [1.0; 8] }, "{name}: block_splat");
+    let sum = Block {
+        values: [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+    };
+    assert_eq!(kernels.block_add(ramp, ones), sum, "{name}: block_add");
+    assert_eq!(
+        kernels.block_add_into(&ramp, &ones),
+        sum,
+        "{name}: block_add_into"
+    );
+    assert_eq!(kernels.block_sum(&ramp), 28.0, "{name}: block_sum");
+}
+
+backend_tests!(structs, structs_give_known_results);
+
+fn callbacks_give_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    let mut values = [5, -1, 3, 3, 0, 9, -7];
+    kernels.sort_ints(&mut values, &mut |a, b| a - b);
+    assert_eq!(values, [-7, -1, 0, 3, 3, 5, 9], "{name}: sort_ints");
+    kernels.sort_ints(&mut values, &mut |a, b| b - a);
+    assert_eq!(
+        values,
+        [9, 5, 3, 3, 0, -1, -7],
+        "{name}: sort_ints descending"
+    );
+    let mut empty: [i32; 0] = [];
+    kernels.sort_ints(&mut empty, &mut |_, _| unreachable!());
+
+    // The closure keeps state across calls.
+    let mut calls = 0;
+    kernels.map_array(&mut values, &mut |x| {
+        calls += 1;
+        x * 2 + calls
+    });
+    assert_eq!(values, [19, 12, 9, 10, 5, 4, -7], "{name}: map_array");
+    assert_eq!(calls, 7, "{name}: map_array calls");
+}
+
+backend_tests!(callbacks, callbacks_give_known_results);
+
+fn enums_give_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    let sides = SHAPES.map(|shape| kernels.shape_sides(shape));
+    assert_eq!(sides, [0, 4, 3, 6], "{name}: shape_sides");
+    let next = SHAPES.map(|shape| kernels.shape_next(shape));
+    assert_eq!(
+        next,
+        [
+            Shape::Square,
+            Shape::Triangle,
+            Shape::Hexagon,
+            Shape::Circle
+        ],
+        "{name}: shape_next"
+    );
+    assert_eq!(kernels.shape_area(Shape::Square, 3.0), 9.0, "{name}: area");
+    let results = OPS.map(|op| kernels.apply_op(op, 12, 5));
+    assert_eq!(results, [17, 7, 60, 2, -12], "{name}: apply_op");
+    assert_eq!(kernels.apply_op(Op::Div, 1, 0), 0, "{name}: 1 / 0");
+}
+
+backend_tests!(enums, enums_give_known_results);
+
+fn layouts_give_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    let instr = kernels.instr_encode(0x41, 3, 0x25, 0x1_2345);
+    assert_eq!(
+        instr,
+        Instr {
+            opcode: 1,
+            dest: 3,
+            src: 5,
+            imm: 0x2345
+        },
+        "{name}: instr_encode keeps the low bits"
+    );
+    assert_eq!(kernels.instr_opcode(instr), 1, "{name}: instr_opcode");
+    assert_eq!(kernels.instr_imm(instr), 0x2345, "{name}: instr_imm");
+    let mut changed = instr;
+    kernels.instr_set_dest(&mut changed, 0xfe);
+    assert_eq!(
+        changed,
+        Instr { dest: 30, ..instr },
+        "{name}: instr_set_dest"
+    );
+    let instrs = [instr, changed, kernels.instr_encode(0, 0, 0, 0xffff)];
+    assert_eq!(
+        kernels.instr_sum_imm(&instrs),
+        2 * 0x2345 + 0xffff,
+        "{name}: instr_sum_imm"
+    );
+
+    assert_eq!(kernels.value_int(-7), Value::Int(-7), "{name}: value_int");
+    assert_eq!(
+        kernels.value_float(0.5),
+        Value::Float(0.5),
+        "{name}: value_float"
+    );
+    assert_eq!(
+        kernels.value_pair(2, -9),
+        Value::Pair(2, -9),
+        "{name}: value_pair"
+    );
+    let values = [Value::Int(1 << 40), Value::Float(-0.25), Value::Pair(3, 4)];
+    let doubles = values.map(|v| kernels.value_to_double(v));
+    assert_eq!(
+        doubles,
+        [1099511627776.0, -0.25, 7.0],
+        "{name}: value_to_double"
+    );
+    assert_eq!(
+        kernels.value_sum(&values),
+        1099511627782.75,
+        "{name}: value_sum"
+    );
+
+    let mut s = Series {
+        data: vec![1.0, 2.5, -4.0],
+    };
+    assert_eq!(kernels.series_sum(&s), -0.5, "{name}: series_sum");
+    kernels.series_scale(&mut s, -2.0);
+    assert_eq!(s.data, [-2.0, -5.0, 8.0], "{name}: series_scale");
+    assert_eq!(
+        kernels.series_sum(&Series::default()),
+        0.0,
+        "{name}: empty series_sum"
+    );
+}
+
+backend_tests!(layouts, layouts_give_known_results);
+
+fn macros_give_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    assert_eq!(kernels.buffer_bytes(), 2048, "{name}: buffer_bytes");
+    assert_eq!(
+        kernels.scaled_buffer_bytes(),
+        256.0,
+        "{name}: scaled_buffer_bytes"
+    );
+    assert_eq!(kernels.set_flag(0x01), 0x81, "{name}: set_flag");
+    assert_eq!(kernels.set_flag(0x80), 0x80, "{name}: set_flag twice");
+    let colors: Vec<i32> = [0, 1, 2, 3, -1].map(|c| kernels.next_color(c)).into();
+    assert_eq!(colors, [1, 2, 0, -1, -1], "{name}: next_color");
+    assert_eq!(kernels.clamp(5, 0, 3), 3, "{name}: clamp above");
+    assert_eq!(kernels.clamp(-5, 0, 3), 0, "{name}: clamp below");
+    assert_eq!(kernels.clamp(2, 0, 3), 2, "{name}: clamp inside");
+    // The C function checks `lo` first, without requiring `lo <= hi`.
+    assert_eq!(
+        kernels.clamp_int(5, 9, 3),
+        9,
+        "{name}: clamp_int with lo > hi"
+    );
+    assert_eq!(kernels.in_range(3, 0, 3), 1, "{name}: in_range at hi");
+    assert_eq!(kernels.in_range(-1, 0, 3), 0, "{name}: in_range below");
+    assert_eq!(
+        kernels.in_range(1, 3, 0),
+        0,
+        "{name}: in_range with lo > hi"
+    );
+    assert_eq!(kernels.clamp_temp(-100), -40, "{name}: clamp_temp");
+    assert_eq!(kernels.clamp_temp(100), 60, "{name}: clamp_temp");
+    assert_eq!(kernels.buffer_offset(3), 24, "{name}: buffer_offset");
+    assert_eq!(
+        kernels.buffer_offset(259),
+        24,
+        "{name}: buffer_offset wraps"
+    );
+}
+
+backend_tests!(macros, macros_give_known_results);
+
+fn overflow_variants_give_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    let (max, min) = (i32::MAX, i32::MIN);
+    assert_eq!(kernels.checked_inc(41), Some(42), "{name}: checked_inc");
+    assert_eq!(kernels.checked_inc(max), None, "{name}: checked_inc(MAX)");
+    assert_eq!(kernels.wrapping_inc(max), min, "{name}: wrapping_inc(MAX)");
+    assert_eq!(
+        kernels.saturating_inc(max),
+        max,
+        "{name}: saturating_inc(MAX)"
+    );
+    assert_eq!(kernels.checked_dec(min), None, "{name}: checked_dec(MIN)");
+    assert_eq!(kernels.wrapping_dec(min), max, "{name}: wrapping_dec(MIN)");
+    assert_eq!(
+        kernels.saturating_dec(min),
+        min,
+        "{name}: saturating_dec(MIN)"
+    );
+
+    assert_eq!(
+        kernels.checked_factorial(12),
+        Some(479_001_600),
+        "{name}: checked_factorial(12)"
+    );
+    assert_eq!(kernels.checked_factorial(13), None, "{name}: 13!");
+    let wrapped = (1..=13).fold(1i32, i32::wrapping_mul);
+    assert_eq!(kernels.wrapping_factorial(13), wrapped, "{name}: 13!");
+    assert_eq!(kernels.saturating_factorial(13), max, "{name}: 13!");
+    assert_eq!(kernels.saturating_factorial(0), 1, "{name}: 0!");
+
+    assert_eq!(
+        kernels.checked_apply_op(Op::Add, max, 1),
+        None,
+        "{name}: MAX + 1"
+    );
+    assert_eq!(
+        kernels.checked_apply_op(Op::Mul, 12, 5),
+        Some(60),
+        "{name}: 12 * 5"
+    );
+    assert_eq!(
+        kernels.checked_apply_op(Op::Div, min, -1),
+        None,
+        "{name}: MIN / -1"
+    );
+    assert_eq!(
+        kernels.wrapping_apply_op(Op::Div, min, -1),
+        min,
+        "{name}: MIN / -1"
+    );
+    assert_eq!(
+        kernels.saturating_apply_op(Op::Div, min, -1),
+        max,
+        "{name}: MIN / -1"
+    );
+    assert_eq!(
+        kernels.checked_apply_op(Op::Neg, min, 0),
+        None,
+        "{name}: -MIN"
+    );
+    assert_eq!(
+        kernels.wrapping_apply_op(Op::Neg, min, 0),
+        min,
+        "{name}: -MIN"
+    );
+    assert_eq!(
+        kernels.saturating_apply_op(Op::Neg, min, 0),
+        max,
+        "{name}: -MIN"
+    );
+    assert_eq!(
+        kernels.saturating_apply_op(Op::Mul, min, 2),
+        min,
+        "{name}: MIN * 2"
+    );
+    assert_eq!(
+        kernels.saturating_apply_op(Op::Sub, min, 1),
+        min,
+        "{name}: MIN - 1"
+    );
+    // Like `apply_op`, dividing by zero gives zero rather than overflowing.
+    assert_eq!(
+        kernels.checked_apply_op(Op::Div, 1, 0),
+        Some(0),
+        "{name}: 1 / 0"
+    );
+    assert_eq!(
+        kernels.saturating_apply_op(Op::Div, 1, 0),
+        0,
+        "{name}: 1 / 0"
+    );
+}
+
+backend_tests!(overflow_variants, overflow_variants_give_known_results);
+
+#[cfg(target_arch = "x86_64")]
+fn simd_gives_known_results<K: Kernels>(kernels: &K) {
+    let name = kernels.name();
+    let avx2 = is_x86_feature_detected!("avx2");
+    let x: Vec<f32> = (0..19).map(|i| i as f32).collect();
+    let expected: Vec<f32> = (0..19).map(|i| 2.0 * i as f32 + 1.0).collect();
+    let mut y = vec![1.0; 19];
+    kernels.saxpy_sse2(2.0, &x, &mut y);
+    assert_eq!(y, expected, "{name}: saxpy_sse2");
+    assert_eq!(kernels.sum_sse2(&x), 171.0, "{name}: sum_sse2");
+    assert_eq!(kernels.sum_sse2(&[]), 0.0, "{name}: sum_sse2 of nothing");
+    let data: Vec<u8> = (0..=255).chain([7; 45]).collect();
+    let counts = kernels.byte_histogram_sse2(&data);
+    assert_eq!(counts[7], 46, "{name}: byte_histogram_sse2");
+    assert_eq!(
+        counts.iter().sum::<u32>(),
+        301,
+        "{name}: byte_histogram_sse2"
+    );
+    if avx2 {
+        let mut y = vec![1.0; 19];
+        kernels.saxpy_avx2(2.0, &x, &mut y);
+        assert_eq!(y, expected, "{name}: saxpy_avx2");
+        assert_eq!(kernels.sum_avx2(&x), 171.0, "{name}: sum_avx2");
+        assert_eq!(
+            kernels.byte_histogram_avx2(&data),
+            counts,
+            "{name}: byte_histogram_avx2"
+        );
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+backend_tests!(simd, simd_gives_known_results);
+
+#[test]
+#[should_panic(expected = "same length")]
+fn c_dot_rejects_different_lengths() {
+    CBackend.dot(&[1.0], &[1.0, 2.0]);
+}
+
+/// Runs `check` with each backend against the first, which the others
+/// must agree with.
+fn agree(check: impl Fn(&dyn Kernels, &dyn Kernels)) {
+    let [reference, others @ ..] = BACKENDS;
+    for other in others {
+        check(other, reference);
+    }
+}
+
+#[test]
+fn inc_and_dec_agree() {
+    agree(|other, reference| {
+        for x in INPUTS {
+            assert_eq!(
+                other.inc(x),
+                reference.inc(x),
+                "inc({x}): {} and {} differ",
+                other.name(),
+                reference.name()
+            );
+            assert_eq!(
+                other.dec(x),
+                reference.dec(x),
+                "dec({x}): {} and {} differ",
+                other.name(),
+                reference.name()
+            );
+        }
+    });
+}
+
+#[test]
+fn math_ops_agree() {
+    agree(|other, reference| {
+        for n in 0..=12 {
+            assert_eq!(other.factorial(n), reference.factorial(n), "factorial({n})");
+        }
+
+        for n in (0..10_000).chain(u32::MAX - 10_000..=u32::MAX) {
+            assert_eq!(other.is_prime(n), reference.is_prime(n), "is_prime({n})");
+        }
+        let a: Vec<f64> = (0..100).map(|i| f64::from(i) * 0.5).collect();
+        let b: Vec<f64> = (0..100).map(|i| 1.0 / f64::from(i + 1)).collect();
+        assert_eq!(other.dot(&a, &b), reference.dot(&a, &b), "dot");
+        let (m, n) = ([1.5, -2.0, 0.25, 4.0], [3.0, 0.5, -1.0, 2.0]);
+        assert_eq!(
+            other.matrix_multiply_2x2(&m, &n),
+            reference.matrix_multiply_2x2(&m, &n),
+            "matrix_multiply_2x2"
+        );
+    });
+}
+
+#[test]
+fn structs_agree() {
+    agree(|other, reference| {
+        let (a, b) = (Vec2 { x: 0.1, y: -2.5 }, Vec2 { x: 3.3, y: 0.7 });
+        assert_eq!(other.vec2_add(a, b), reference.vec2_add(a, b), "vec2_add");
+        assert_eq!(
+            other.vec2_dot(&a, &b),
+            reference.vec2_dot(&a, &b),
+            "vec2_dot"
+        );
+        let s = Sample {
+            id: -3,
+            weight: 0.3,
+            value: 1e10,
+        };
+        assert_eq!(
+            other.sample_scale(s, 0.7),
+            reference.sample_scale(s, 0.7),
+            "sample_scale"
+        );
+        let (mut s1, mut s2) = (s, s);
+        other.sample_scale_in_place(&mut s1, 0.7);
+        reference.sample_scale_in_place(&mut s2, 0.7);
+        assert_eq!(s1, s2, "sample_scale_in_place");
+        let x = Block {
+            values: std::array::from_fn(|i| 1.0 / (i as f64 + 1.0)),
+        };
+        let y = other.block_splat(0.1);
+        assert_eq!(y, reference.block_splat(0.1), "block_splat");
+        assert_eq!(
+            other.block_add(x, y),
+            reference.block_add(x, y),
+            "block_add"
+        );
+        assert_eq!(
+            other.block_add_into(&x, &y),
+            reference.block_add_into(&x, &y),
+            "block_add_into"
+        );
+        assert_eq!(other.block_sum(&x), reference.block_sum(&x), "block_sum");
+    });
+}
+
+#[test]
+fn callbacks_agree() {
+    agree(|other, reference| {
+        let values: Vec<i32> = (0..1000).map(|i| (i * 7919) % 1009 - 500).collect();
+        let (mut sorted, mut expected) = (values.clone(), values.clone());
+        other.sort_ints(&mut sorted, &mut |a, b| a.cmp(b) as i32);
+        reference.sort_ints(&mut expected, &mut |a, b| a.cmp(b) as i32);
+        assert_eq!(sorted, expected, "sort_ints");
+        // Elements that compare equal may end up in any order.
+        let (mut sorted, mut expected) = (values.clone(), values.clone());
+        other.sort_ints(&mut sorted, &mut |a, b| {
+            (a.abs() % 10).cmp(&(b.abs() % 10)) as i32
+        });
+        reference.sort_ints(&mut expected, &mut |a, b| {
+            (a.abs() % 10).cmp(&(b.abs() % 10)) as i32
+        });
+        let digits = |v: &[i32]| v.iter().map(|x| x.abs() % 10).collect::<Vec<_>>();
+        assert_eq!(
+            digits(&sorted),
+            digits(&expected),
+            "sort_ints by last digit"
+        );
+        let (mut mapped, mut expected) = (values.clone(), values);
+        other.map_array(&mut mapped, &mut |x| x.wrapping_mul(x) ^ 0x55);
+        reference.map_array(&mut expected, &mut |x| x.wrapping_mul(x) ^ 0x55);
+        assert_eq!(mapped, expected, "map_array");
+    });
+}
+
+#[test]
+fn enums_agree() {
+    agree(|other, reference| {
+        for shape in SHAPES {
+            assert_eq!(other.shape_sides(shape), reference.shape_sides(shape));
+            assert_eq!(other.shape_next(shape), reference.shape_next(shape));
+            for size in [0.0, 0.5, 1.0, 7.25, 1e100] {
+                assert_eq!(
+                    other.shape_area(shape, size),
+                    reference.shape_area(shape, size),
+                    "shape_area({shape:?}, {size})"
+                );
+            }
+        }
+
+        for op in OPS {
+            for (a, b) in [(0, 0), (7, -3), (-100, 9), (1 << 15, 1 << 15)] {
+                assert_eq!(
+                    other.apply_op(op, a, b),
+                    reference.apply_op(op, a, b),
+                    "apply_op({op:?}, {a}, {b})"
+                );
+            }
+        }
+    });
+}
+
+#[test]
+fn layouts_agree() {
+    agree(|other, reference| {
+        let instrs: Vec<Instr> = (0..1000u32)
+            .map(|i| reference.instr_encode(i, i * 3, i * 7, i * 7919))
+            .collect();
+        for (i, &instr) in instrs.iter().enumerate() {
+            assert_eq!(
+                other.instr_encode(i as u32, i as u32 * 3, i as u32 * 7, i as u32 * 7919),
+                instr,
+                "instr_encode({i})"
+            );
+            assert_eq!(other.instr_opcode(instr), reference.instr_opcode(instr));
+            assert_eq!(other.instr_imm(instr), reference.instr_imm(instr));
+            let (mut a, mut b) = (instr, instr);
+            other.instr_set_dest(&mut a, i as u32);
+            reference.instr_set_dest(&mut b, i as u32);
+            assert_eq!(a, b, "instr_set_dest({i})");
+        }
+        assert_eq!(
+            other.instr_sum_imm(&instrs),
+            reference.instr_sum_imm(&instrs),
+            "instr_sum_imm"
+        );
+
+        let values: Vec<Value> = (0..300)
+            .map(|i| match i % 3 {
+                0 => other.value_int(i64::from(i) << 33),
+                1 => other.value_float(f64::from(i) / 7.0),
+                _ => other.value_pair(i, -i * i),
+            })
+            .collect();
+        for &v in &values {
+            assert_eq!(other.value_to_double(v), reference.value_to_double(v));
+        }
+        assert_eq!(
+            other.value_sum(&values),
+            reference.value_sum(&values),
+            "value_sum"
+        );
+
+        let mut a = Series {
+            data: (0..100).map(|i| f64::from(i).sqrt()).collect(),
+        };
+        let mut b = a.clone();
+        assert_eq!(other.series_sum(&a), reference.series_sum(&b), "series_sum");
+        other.series_scale(&mut a, 1.5);
+        reference.series_scale(&mut b, 1.5);
+        assert_eq!(a, b, "series_scale");
+    });
+}
+
+#[test]
+fn macros_agree() {
+    agree(|other, reference| {
+        for x in -100..100 {
+            assert_eq!(
+                other.clamp_temp(x),
+                reference.clamp_temp(x),
+                "clamp_temp({x})"
+            );
+            assert_eq!(
+                other.next_color(x),
+                reference.next_color(x),
+                "next_color({x})"
+            );
+            assert_eq!(
+                other.clamp(x, -x / 2, 17),
+                reference.clamp(x, -x / 2, 17),
+                "clamp({x})"
+            );
+            assert_eq!(
+                other.in_range(x, -x / 2, 17),
+                reference.in_range(x, -x / 2, 17),
+                "in_range({x})"
+            );
+        }
+        for i in [0, 1, 255, 256, 1000, usize::MAX] {
+            assert_eq!(
+                other.buffer_offset(i),
+                reference.buffer_offset(i),
+                "buffer_offset({i})"
+            );
+        }
+    });
+}
+
+#[test]
+fn overflow_variants_agree() {
+    agree(|other, reference| {
+        for x in [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX] {
+            assert_eq!(
+                other.checked_inc(x),
+                reference.checked_inc(x),
+                "checked_inc({x})"
+            );
+            assert_eq!(
+                other.wrapping_inc(x),
+                reference.wrapping_inc(x),
+                "wrapping_inc({x})"
+            );
+            assert_eq!(
+                other.saturating_inc(x),
+                reference.saturating_inc(x),
+                "saturating_inc({x})"
+            );
+            assert_eq!(
+                other.checked_dec(x),
+                reference.checked_dec(x),
+                "checked_dec({x})"
+            );
+            assert_eq!(
+                other.wrapping_dec(x),
+                reference.wrapping_dec(x),
+                "wrapping_dec({x})"
+            );
+            assert_eq!(
+                other.saturating_dec(x),
+                reference.saturating_dec(x),
+                "saturating_dec({x})"
+            );
+        }
+        for n in 0..=40 {
+            assert_eq!(
+                other.checked_factorial(n),
+                reference.checked_factorial(n),
+                "checked_factorial({n})"
+            );
+            assert_eq!(
+                other.wrapping_factorial(n),
+                reference.wrapping_factorial(n),
+                "wrapping_factorial({n})"
+            );
+            assert_eq!(
+                other.saturating_factorial(n),
+                reference.saturating_factorial(n),
+                "saturating_factorial({n})"
+            );
+        }
+
+        for op in OPS {
+            let edges = [i32::MIN, -2, -1, 0, 1, 2, i32::MAX];
+            for (a, b) in edges.into_iter().flat_map(|a| edges.map(|b| (a, b))) {
+                assert_eq!(
+                    other.checked_apply_op(op, a, b),
+                    reference.checked_apply_op(op, a, b),
+                    "checked_apply_op({op:?}, {a}, {b})"
+                );
+                assert_eq!(
+                    other.wrapping_apply_op(op, a, b),
+                    reference.wrapping_apply_op(op, a, b),
+                    "wrapping_apply_op({op:?}, {a}, {b})"
+                );
+                assert_eq!(
+                    other.saturating_apply_op(op, a, b),
+                    reference.saturating_apply_op(op, a, b),
+                    "saturating_apply_op({op:?}, {a}, {b})"
+                );
+            }
+        }
+    });
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn simd_agree() {
+    agree(|other, reference| {
+        let avx2 = is_x86_feature_detected!("avx2");
+        let x: Vec<f32> = (0..1003).map(|i| (i as f32 * 0.37).sin()).collect();
+        let (mut y, mut z) = (x.clone(), x.clone());
+        other.saxpy_sse2(-1.5, &x, &mut y);
+        reference.saxpy_sse2(-1.5, &x, &mut z);
+        assert_eq!(y, z, "saxpy_sse2");
+        assert_eq!(other.sum_sse2(&x), reference.sum_sse2(&x), "sum_sse2");
+        let data: Vec<u8> = (0..1003u32).map(|i| (i * i % 251) as u8).collect();
+        assert_eq!(
+            other.byte_histogram_sse2(&data),
+            reference.byte_histogram_sse2(&data),
+            "byte_histogram_sse2"
+        );
+        if avx2 {
+            other.saxpy_avx2(-1.5, &x, &mut y);
+            reference.saxpy_avx2(-1.5, &x, &mut z);
+            assert_eq!(y, z, "saxpy_avx2");
+            assert_eq!(other.sum_avx2(&x), reference.sum_avx2(&x), "sum_avx2");
+            assert_eq!(
+                other.byte_histogram_avx2(&data),
+                reference.byte_histogram_avx2(&data),
+                "byte_histogram_avx2"
+            );
+        }
+    });
+}
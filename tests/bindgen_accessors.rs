@@ -0,0 +1,65 @@
+//! Checks that the Rust kernels over bindgen's types read and write the
+//! bitfields and unions the way the C functions do.
+
+use ir_comparison::bindgen_accessors;
+use ir_comparison::c_ffi::{bitfields, unions};
+
+#[test]
+fn bitfield_accessors_match_c() {
+    let instrs: Vec<bitfields::Instr> = (0..2000u32)
+        .map(|i| {
+            let (opcode, dest, src, imm) = (i, i * 3, i * 7, i.wrapping_mul(7919));
+            let c = bitfields::instr_encode_safe(opcode, dest, src, imm);
+            let rust = bindgen_accessors::instr_encode(opcode, dest, src, imm);
+            assert_eq!(rust._bitfield_1, c._bitfield_1, "instr_encode({i})");
+            c
+        })
+        .collect();
+    for (i, &instr) in instrs.iter().enumerate() {
+        assert_eq!(
+            bindgen_accessors::instr_opcode(instr),
+            bitfields::instr_opcode_safe(instr),
+            "instr_opcode({i})"
+        );
+        assert_eq!(
+            bindgen_accessors::instr_imm(instr),
+            bitfields::instr_imm_safe(instr),
+            "instr_imm({i})"
+        );
+        let (mut rust, mut c) = (instr, instr);
+        bindgen_accessors::instr_set_dest(&mut rust, i as u32);
+        bitfields::instr_set_dest_safe(&mut c, i as u32);
+        assert_eq!(rust._bitfield_1, c._bitfield_1, "instr_set_dest({i})");
+    }
+    assert_eq!(
+        bindgen_accessors::instr_sum_imm(&instrs),
+        bitfields::instr_sum_imm_safe(&instrs)
+    );
+}
+
+#[test]
+fn union_reads_match_c() {
+    let mut values: Vec<unions::Value> = (0..300)
+        .map(|i| match i % 3 {
+            0 => unions::value_int_safe(i64::from(i) * -12345),
+            1 => unions::value_float_safe(f64::from(i) * 0.125),
+            _ => unions::value_pair_safe(i, i32::MAX - i),
+        })
+        .collect();
+    // A kind that isn't in the enum, which both sides read as 0.
+    values.push(unions::Value {
+        kind: 7,
+        data: unions::ValueData { i: 1 },
+    });
+    for (i, &v) in values.iter().enumerate() {
+        assert_eq!(
+            bindgen_accessors::value_to_double(v),
+            unions::value_to_double_safe(v),
+            "value_to_double({i})"
+        );
+    }
+    assert_eq!(
+        bindgen_accessors::value_sum(&values),
+        unions::value_sum_safe(&values)
+    );
+}
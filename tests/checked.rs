@@ -0,0 +1,64 @@
+//! Checks that the `c_ffi::checked` wrappers turn the arguments that would be
+//! undefined behavior in C into errors, and call C with all others.
+
+use ir_comparison::c_ffi::checked::{self, PreconditionError};
+use ir_comparison::c_ffi::enums;
+
+#[test]
+fn integer_overflow_is_an_error() {
+    assert_eq!(checked::inc(41), Ok(42));
+    assert_eq!(checked::inc(i32::MAX), Err(PreconditionError::Overflow));
+    assert_eq!(checked::dec(i32::MIN + 1), Ok(i32::MIN));
+    assert_eq!(checked::dec(i32::MIN), Err(PreconditionError::Overflow));
+    assert_eq!(checked::factorial(12), Ok(479_001_600));
+    assert_eq!(checked::factorial(13), Err(PreconditionError::Overflow));
+    assert_eq!(checked::factorial(-5), Ok(1));
+}
+
+#[test]
+fn apply_op_overflow_is_an_error() {
+    let overflow = Err(PreconditionError::Overflow);
+    assert_eq!(checked::apply_op(enums::Op_OP_ADD, i32::MAX, 1), overflow);
+    assert_eq!(checked::apply_op(enums::Op_OP_SUB, i32::MIN, 1), overflow);
+    assert_eq!(
+        checked::apply_op(enums::Op_OP_MUL, 1 << 16, 1 << 16),
+        overflow
+    );
+    assert_eq!(checked::apply_op(enums::Op_OP_DIV, i32::MIN, -1), overflow);
+    assert_eq!(checked::apply_op(enums::Op_OP_NEG, i32::MIN, 0), overflow);
+    assert_eq!(checked::apply_op(enums::Op_OP_MUL, 12, 5), Ok(60));
+    assert_eq!(checked::apply_op(enums::Op_OP_DIV, i32::MIN, 0), Ok(0));
+    assert_eq!(checked::apply_op(0, i32::MAX, i32::MAX), Ok(0));
+}
+
+#[test]
+fn short_slices_are_an_error() {
+    let (a, b) = ([1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]);
+    let mut result = [0.0; 4];
+    assert_eq!(checked::matrix_multiply_2x2(&a, &b, &mut result), Ok(()));
+    assert_eq!(result, [19.0, 22.0, 43.0, 50.0]);
+    assert_eq!(
+        checked::matrix_multiply_2x2(&a[..3], &b, &mut result),
+        Err(PreconditionError::TooShort {
+            param: "a",
+            len: 3,
+            needed: 4
+        })
+    );
+    assert_eq!(
+        checked::matrix_multiply_2x2(&a, &b, &mut [0.0; 2]),
+        Err(PreconditionError::TooShort {
+            param: "result",
+            len: 2,
+            needed: 4
+        })
+    );
+    assert_eq!(checked::vector_dot_product(&a, &b), Ok(70.0));
+    assert_eq!(
+        checked::vector_dot_product(&a, &b[..1]),
+        Err(PreconditionError::LengthMismatch {
+            len: 4,
+            other_len: 1
+        })
+    );
+}
@@ -0,0 +1,77 @@
+//! Checks that the bindings of `enums.h` in every enum style call the same C
+//! functions with the same values.
+
+use ir_comparison::c_ffi::{
+    enums, enums_moduleconsts, enums_newtype, enums_rust, enums_rust_non_exhaustive,
+};
+
+#[test]
+fn every_style_gives_the_same_results() {
+    let shapes = [
+        enums::Shape_SHAPE_CIRCLE,
+        enums::Shape_SHAPE_SQUARE,
+        enums::Shape_SHAPE_TRIANGLE,
+        enums::Shape_SHAPE_HEXAGON,
+    ];
+    let rust = [
+        enums_rust::Shape::SHAPE_CIRCLE,
+        enums_rust::Shape::SHAPE_SQUARE,
+        enums_rust::Shape::SHAPE_TRIANGLE,
+        enums_rust::Shape::SHAPE_HEXAGON,
+    ];
+    let non_exhaustive = [
+        enums_rust_non_exhaustive::Shape::SHAPE_CIRCLE,
+        enums_rust_non_exhaustive::Shape::SHAPE_SQUARE,
+        enums_rust_non_exhaustive::Shape::SHAPE_TRIANGLE,
+        enums_rust_non_exhaustive::Shape::SHAPE_HEXAGON,
+    ];
+    for (i, &shape) in shapes.iter().enumerate() {
+        let sides = enums::shape_sides_safe(shape);
+        let area = enums::shape_area_safe(shape, 2.5);
+        let next = enums::shape_next_safe(shape);
+
+        assert_eq!(enums_rust::shape_sides_safe(rust[i]), sides);
+        assert_eq!(enums_rust::shape_area_safe(rust[i], 2.5), area);
+        assert_eq!(enums_rust::shape_next_safe(rust[i]) as u32, next);
+
+        let shape_ne = non_exhaustive[i];
+        assert_eq!(enums_rust_non_exhaustive::shape_sides_safe(shape_ne), sides);
+        assert_eq!(
+            enums_rust_non_exhaustive::shape_area_safe(shape_ne, 2.5),
+            area
+        );
+        assert_eq!(
+            enums_rust_non_exhaustive::shape_next_safe(shape_ne) as u32,
+            next
+        );
+
+        assert_eq!(enums_moduleconsts::shape_sides_safe(shape), sides);
+        assert_eq!(enums_moduleconsts::shape_area_safe(shape, 2.5), area);
+        assert_eq!(enums_moduleconsts::shape_next_safe(shape), next);
+
+        let newtype = enums_newtype::Shape(shape);
+        assert_eq!(enums_newtype::shape_sides_safe(newtype), sides);
+        assert_eq!(enums_newtype::shape_area_safe(newtype, 2.5), area);
+        assert_eq!(enums_newtype::shape_next_safe(newtype).0, next);
+    }
+}
+
+#[test]
+fn constants_have_the_c_values() {
+    assert_eq!(enums::Op_OP_DIV, 8);
+    assert_eq!(enums_rust::Op::OP_DIV as u32, 8);
+    assert_eq!(enums_rust_non_exhaustive::Op::OP_NEG as u32, 16);
+    assert_eq!(enums_moduleconsts::Op::OP_MUL, 4);
+    assert_eq!(enums_newtype::Op::OP_SUB, enums_newtype::Op(2));
+}
+
+#[test]
+fn integer_styles_pass_values_outside_the_enum() {
+    // Only the integer styles can express this; a Rust enum can't hold 99.
+    assert_eq!(enums::shape_sides_safe(99), -1);
+    assert_eq!(enums_moduleconsts::apply_op_safe(3, 1, 2), 0);
+    assert_eq!(
+        enums_newtype::shape_area_safe(enums_newtype::Shape(99), 1.0),
+        0.0
+    );
+}
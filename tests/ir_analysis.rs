@@ -0,0 +1,207 @@
+//! Checks the IR reader on a hand-written module.
+
+use ir_comparison::ir_analysis::{self, FunctionStats, Overflow};
+
+const IR: &str = r#"
+; ModuleID = 'example'
+declare i32 @sort_ints(ptr, i64, ptr, ptr)
+
+; ir_comparison::callback_variants::sort_ints_c_unwind
+define void @_ZN13ir_comparison17callback_variants18sort_ints_c_unwind17h0123456789abcdefE(ptr %values) unnamed_addr #0 personality ptr @rust_eh_personality {
+start:
+  %x = invoke i32 @sort_ints(ptr %values, i64 3, ptr null, ptr null)
+          to label %bb1 unwind label %cleanup
+
+bb1:                                              ; preds = %start
+  ret void
+
+cleanup:                                          ; preds = %start
+  %lp = landingpad { ptr, i32 }
+          cleanup
+  tail call void @llvm.trap()
+  unreachable
+}
+
+define internal i32 @"_ZN4core3ptr85drop_in_place$LT$std..rt..lang_start$LT$$LP$$RP$$GT$..$u7b$$u7b$closure$u7d$$u7d$$GT$17h0123456789abcdefE"() {
+  %1 = call i32 @map_array()
+  ret i32 %1
+}
+
+define dso_local i32 @inc(i32 noundef %0) #0 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+define dso_local i32 @buffer_bytes() #0 {
+  ret i32 2048, !dbg !12
+}
+
+define dso_local double @scaled_buffer_bytes() #0 {
+  ret double 2.560000e+02
+}
+
+define dso_local zeroext i1 @checked_inc__input_extern(i32 noundef %0, ptr noundef %1) #0 {
+  %3 = tail call { i32, i1 } @llvm.sadd.with.overflow.i32(i32 %0, i32 1)
+  %4 = extractvalue { i32, i1 } %3, 1
+  %5 = extractvalue { i32, i1 } %3, 0
+  store i32 %5, ptr %1, align 4
+  ret i1 %4
+}
+
+; ir_comparison::overflow_variants::wrapping_inc
+define noundef i32 @_ZN13ir_comparison17overflow_variants12wrapping_inc17h90ac4995975d17d2E(i32 noundef %x) unnamed_addr #1 {
+start:
+  %_0 = add i32 %x, 1
+  ret i32 %_0
+}
+"#;
+
+#[test]
+fn counts_instructions_per_function() {
+    let functions = ir_analysis::functions(IR);
+    let names: Vec<&str> = functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "ir_comparison::callback_variants::sort_ints_c_unwind",
+            "core::ptr::drop_in_place<std::rt::lang_start<()>::{{closure}}>",
+            "inc",
+            "buffer_bytes",
+            "scaled_buffer_bytes",
+            "checked_inc__input_extern",
+            "ir_comparison::overflow_variants::wrapping_inc",
+        ]
+    );
+    assert_eq!(
+        functions[0].stats,
+        FunctionStats {
+            instructions: 5,
+            calls: 1,
+            invokes: 1,
+            landing_pads: 1,
+            branches: 0,
+            no_wrap: 0,
+            overflow_intrinsics: 0,
+            vector_bits: 0,
+        }
+    );
+    assert_eq!(functions[1].stats.calls, 1);
+    assert_eq!(
+        functions[2].stats,
+        FunctionStats {
+            instructions: 2,
+            calls: 0,
+            invokes: 0,
+            landing_pads: 0,
+            branches: 0,
+            no_wrap: 1,
+            overflow_intrinsics: 0,
+            vector_bits: 0,
+        }
+    );
+    assert_eq!(functions[5].stats.calls, 1);
+    assert_eq!(functions[5].stats.overflow_intrinsics, 1);
+    assert_eq!(functions[6].stats.no_wrap, 0);
+}
+
+#[test]
+fn finds_functions_that_fold_to_a_constant() {
+    let functions = ir_analysis::functions(IR);
+    let constants: Vec<Option<&str>> = functions.iter().map(|f| f.constant.as_deref()).collect();
+    assert_eq!(
+        constants,
+        [
+            None,
+            None,
+            None,
+            Some("i32 2048"),
+            Some("double 2.560000e+02"),
+            None,
+            None,
+        ]
+    );
+}
+
+#[test]
+fn counts_conditional_branches() {
+    let ir = r#"
+define i32 @factorial_any(i32 %n) {
+start:
+  %ok = icmp slt i32 %n, 13
+  br i1 %ok, label %call, label %err
+
+call:
+  %r = tail call i32 @factorial(i32 %n)
+  switch i32 %r, label %done [
+    i32 0, label %err
+    i32 1, label %done
+  ]
+
+err:
+  br label %done
+
+done:
+  ret i32 0
+}
+"#;
+    let functions = ir_analysis::functions(ir);
+    assert_eq!(functions[0].stats.branches, 2);
+    assert_eq!(functions[0].stats.instructions, 6);
+}
+
+#[test]
+fn measures_the_widest_vector_loaded_or_stored() {
+    let ir = r#"
+define void @saxpy(float %a, ptr %x, ptr %y, i64 %n) {
+vector.body:
+  %mask = icmp ule <8 x i64> %index, %trip.count
+  %wide.x = call <8 x float> @llvm.masked.load.v8f32.p0(ptr %x, i32 4, <8 x i1> %mask, <8 x float> poison)
+  %wide.y = load <4 x float>, ptr %y, align 4
+  %sum = fadd <8 x float> %wide.x, %wide.x
+  store <2 x double> %wide.d, ptr %y, align 8
+  ret void
+}
+
+define i32 @scalar(ptr %p) {
+  %v = load i32, ptr %p, align 4
+  %splat = shufflevector <16 x i8> %b, <16 x i8> poison, <16 x i32> zeroinitializer
+  ret i32 %v
+}
+"#;
+    let functions = ir_analysis::functions(ir);
+    assert_eq!(functions[0].stats.vector_bits, 256);
+    assert_eq!(functions[1].stats.vector_bits, 0);
+}
+
+#[test]
+fn tags_overflow_variants_by_kernel() {
+    let functions = ir_analysis::functions(IR);
+    let variants: Vec<(&str, Overflow, &str)> = ir_analysis::overflow_variants(&functions)
+        .iter()
+        .map(|v| (v.kernel, v.overflow, v.function.name.as_str()))
+        .collect();
+    assert_eq!(
+        variants,
+        [
+            ("inc", Overflow::Unchecked, "inc"),
+            ("inc", Overflow::Checked, "checked_inc__input_extern"),
+            (
+                "inc",
+                Overflow::Wrapping,
+                "ir_comparison::overflow_variants::wrapping_inc"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn leaves_other_symbols_alone() {
+    assert_eq!(
+        ir_analysis::demangle("matrix_multiply_2x2"),
+        "matrix_multiply_2x2"
+    );
+    assert_eq!(
+        ir_analysis::demangle("_RNvCs1234_4main4main"),
+        "_RNvCs1234_4main4main"
+    );
+}
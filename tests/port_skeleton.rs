@@ -0,0 +1,101 @@
+//! Runs the port_skeleton command on a fixture binding module and checks
+//! the ports it writes.
+
+// Shared with the command and the build script; not every item is needed
+// here.
+#[allow(dead_code)]
+#[path = "../src/bin/port_skeleton.rs"]
+mod port_skeleton;
+#[allow(dead_code)]
+#[path = "../build/safe_wrappers.rs"]
+mod safe_wrappers;
+
+use std::fs;
+use std::path::PathBuf;
+
+use quote::ToTokens;
+use syn::Item;
+
+const BINDINGS: &str = r#"
+#[doc = r" Bindings for `c_src/kernels.h`."]
+pub mod kernels {
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+unsafe extern "C" {
+    #[doc = " Moves `p` by `d`."]
+    pub fn shift(p: Point, d: ::std::os::raw::c_int) -> Point;
+}
+unsafe extern "C" {
+    #[doc = " @ir flag(return)"]
+    pub fn is_origin(p: Point) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " @ir slice(x, n)"]
+    pub fn total(x: *const f64, n: usize) -> f64;
+}
+unsafe extern "C" {
+    #[doc = " @ir checked(result)"]
+    pub fn checked_twice(
+        x: ::std::os::raw::c_int,
+        result: *mut ::std::os::raw::c_int,
+    ) -> bool;
+}
+}
+"#;
+
+/// Generates the safe wrappers of `bindings` and the skeletons of their
+/// ports into a directory named `test`, with an empty `rust_port.rs`, and
+/// returns the port module `kernels` as `(name, signature)` pairs.
+fn skeletons(test: &str, bindings: &str) -> Vec<(String, String)> {
+    let out_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(test);
+    let src = out_path.join("src");
+    let _ = fs::remove_dir_all(&src);
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("rust_port.rs"), "//! Ports.\n").unwrap();
+    safe_wrappers::generate(bindings, &out_path);
+    port_skeleton::write_skeletons(bindings, &src, &out_path.join("safe_wrappers"));
+
+    let root = fs::read_to_string(src.join("rust_port.rs")).unwrap();
+    assert_eq!(root, "//! Ports.\npub mod kernels;\n");
+    let text = fs::read_to_string(src.join("rust_port/kernels.rs")).unwrap();
+    syn::parse_file(&text)
+        .unwrap_or_else(|err| panic!("the skeletons don't parse: {err}\n{text}"))
+        .items
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::Fn(func) => Some(func.sig),
+            _ => None,
+        })
+        .map(|mut sig| {
+            let name = sig.ident.to_string();
+            sig.ident = syn::parse_quote!(f);
+            (name, sig.to_token_stream().to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn writes_a_port_per_function() {
+    assert_eq!(
+        skeletons("writes_a_port_per_function", BINDINGS),
+        [
+            (
+                "shift",
+                "fn f (p : crate :: c_ffi :: kernels :: Point , d : i32) \
+                 -> crate :: c_ffi :: kernels :: Point",
+            ),
+            (
+                "is_origin",
+                "fn f (p : crate :: c_ffi :: kernels :: Point) -> bool",
+            ),
+            ("total", "fn f (x : & [f64]) -> f64"),
+            // Not `crate::c_ffi::kernels::Option`.
+            ("checked_twice", "fn f (x : i32) -> Option < i32 >"),
+        ]
+        .map(|(name, sig)| (name.to_owned(), sig.to_owned()))
+    );
+}
@@ -0,0 +1,330 @@
+//! Checks the `@ir` annotation parser of the build script, and the safe
+//! wrappers it generates for each annotation.
+
+// Shared with the build script; not every item is needed here.
+#[allow(dead_code)]
+#[path = "../build/safe_wrappers.rs"]
+mod safe_wrappers;
+
+use std::fs;
+use std::path::PathBuf;
+
+use safe_wrappers::Annotation;
+
+const BINDINGS: &str = r#"
+pub mod kernels {
+unsafe extern "C" {
+    #[doc = " @ir slice(a, b, len)"]
+    pub fn dot(a: *const f64, b: *const f64, len: ::std::os::raw::c_int) -> f64;
+}
+unsafe extern "C" {
+    #[doc = " @ir array(m, 4)\n @ir out(result, 4)"]
+    pub fn transpose(m: *const f64, result: *mut f64);
+}
+unsafe extern "C" {
+    #[doc = " @ir ref(p)\n @ir out(len)"]
+    pub fn norm(p: *mut Point, len: *mut f64) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " @ir flag(on)\n @ir flag(return)"]
+    pub fn toggle(on: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    #[doc = " @ir checked(result)"]
+    pub fn checked_inc(x: ::std::os::raw::c_int, result: *mut ::std::os::raw::c_int) -> bool;
+}
+unsafe extern "C" {
+    #[doc = " @ir slice(x, n)\n @ir target_feature(avx2)"]
+    pub fn sum_avx2(x: *const f32, n: usize) -> f32;
+}
+unsafe extern "C" {
+    #[doc = " @ir callback(f, context)\n @ir unwind(f)"]
+    pub fn each(
+        f: ::std::option::Option<
+            unsafe extern "C" fn(x: ::std::os::raw::c_int, context: *mut ::std::os::raw::c_void),
+        >,
+        context: *mut ::std::os::raw::c_void,
+    );
+}
+unsafe extern "C" {
+    #[doc = " @ir callback(f, context)"]
+    pub fn visit(
+        f: ::std::option::Option<
+            unsafe extern "C" fn(x: ::std::os::raw::c_int, context: *mut ::std::os::raw::c_void),
+        >,
+        context: *mut ::std::os::raw::c_void,
+    );
+}
+unsafe extern "C" {
+    pub fn unannotated(p: *const f64) -> f64;
+}
+pub struct Series {
+    pub len: usize,
+    pub data: __IncompleteArrayField<f64>,
+}
+unsafe extern "C" {
+    pub fn series_sum(s: *const Series) -> f64;
+}
+}
+"#;
+
+fn strings(names: &[&str]) -> Vec<String> {
+    names.iter().map(|&name| name.to_owned()).collect()
+}
+
+/// Generates the wrappers of `bindings` into a directory named `test`, and
+/// returns those of module `kernels` without line breaks, so they read as
+/// in the source.
+fn generate(test: &str, bindings: &str) -> String {
+    let out_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(test);
+    safe_wrappers::generate(bindings, &out_path);
+    let text = fs::read_to_string(out_path.join("safe_wrappers/kernels.rs")).unwrap();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+fn parses_each_annotation() {
+    assert_eq!(
+        Annotation::parse(" slice(a, b, len) "),
+        Ok(Annotation::Slice {
+            pointers: strings(&["a", "b"]),
+            len: "len".to_owned(),
+        })
+    );
+    assert_eq!(
+        Annotation::parse("array(a, 4)"),
+        Ok(Annotation::Array {
+            pointer: "a".to_owned(),
+            len: 4,
+        })
+    );
+    assert_eq!(
+        Annotation::parse("ref(p)"),
+        Ok(Annotation::Ref {
+            pointer: "p".to_owned(),
+        })
+    );
+    assert_eq!(
+        Annotation::parse("out(result)"),
+        Ok(Annotation::Out {
+            pointer: "result".to_owned(),
+            len: None,
+        })
+    );
+    assert_eq!(
+        Annotation::parse("out(result, 256)"),
+        Ok(Annotation::Out {
+            pointer: "result".to_owned(),
+            len: Some(256),
+        })
+    );
+    assert_eq!(
+        Annotation::parse("flag(return)"),
+        Ok(Annotation::Flag {
+            param: "return".to_owned(),
+        })
+    );
+    assert_eq!(
+        Annotation::parse("callback(f, context)"),
+        Ok(Annotation::Callback {
+            callback: "f".to_owned(),
+            context: "context".to_owned(),
+        })
+    );
+    assert_eq!(
+        Annotation::parse("checked(result)"),
+        Ok(Annotation::Checked {
+            pointer: "result".to_owned(),
+        })
+    );
+    assert_eq!(
+        Annotation::parse("unwind(f)"),
+        Ok(Annotation::Unwind {
+            callback: "f".to_owned(),
+        })
+    );
+    assert_eq!(
+        Annotation::parse("target_feature(sse4.1)"),
+        Ok(Annotation::TargetFeature {
+            feature: "sse4.1".to_owned(),
+        })
+    );
+}
+
+#[test]
+fn writes_annotations_in_one_spelling() {
+    for text in [
+        "slice(a,b,  len)",
+        "array( a , 4 )",
+        "ref(p)",
+        "out(result)",
+        "out(result,4)",
+        "flag(x)",
+        "callback(f,context)",
+        "checked(result)",
+        "unwind(f)",
+        "target_feature(avx2)",
+    ] {
+        let annotation = Annotation::parse(text).unwrap();
+        let written = annotation.to_string();
+        let reparsed = Annotation::parse(written.strip_prefix("@ir").unwrap());
+        assert_eq!(reparsed.as_ref(), Ok(&annotation), "{text}");
+    }
+    let slice = Annotation::parse("slice(a,b,  len)").unwrap();
+    assert_eq!(slice.to_string(), "@ir slice(a, b, len)");
+}
+
+#[test]
+fn rejects_malformed_annotations() {
+    let error = |text| Annotation::parse(text).unwrap_err();
+    assert_eq!(error("slice"), "expected `kind(arguments)`, found `slice`");
+    assert_eq!(
+        error("slice(a, b"),
+        "expected `kind(arguments)`, found `slice(a, b`"
+    );
+    assert_eq!(error("slice(len)"), "wrong number of arguments to `slice`");
+    assert_eq!(error("ref(a, b)"), "wrong number of arguments to `ref`");
+    assert_eq!(
+        error("checked()"),
+        "`` is not a parameter name, a length or a feature"
+    );
+    assert_eq!(
+        error("ref(*a)"),
+        "`*a` is not a parameter name, a length or a feature"
+    );
+    assert_eq!(
+        error("array(a, n)"),
+        "`array` needs a constant length, found `n`"
+    );
+    assert_eq!(
+        error("out(a, -1)"),
+        "`-1` is not a parameter name, a length or a feature"
+    );
+    assert!(error("pointer(a)").starts_with("unknown annotation `pointer`"));
+}
+
+#[test]
+fn renders_a_wrapper_per_annotation() {
+    let wrappers = generate("renders_a_wrapper_per_annotation", BINDINGS);
+    let expected = [
+        // The lengths must match, and a C `int` length is converted.
+        "pub fn dot_safe(a: &[f64], b: &[f64]) -> f64 { \
+         assert_eq!(a.len(), b.len(), \"`a` and `b` must have the same length\"); \
+         unsafe { dot(a.as_ptr(), b.as_ptr(), a.len().try_into().expect(\"slice is too long\")) } }",
+        "pub fn transpose_safe(m: &[f64; 4]) -> [f64; 4] { \
+         let mut result = ::std::mem::MaybeUninit::<[f64; 4]>::uninit(); \
+         unsafe { transpose(m.as_ptr(), result.as_mut_ptr().cast()) }; \
+         unsafe { result.assume_init() } }",
+        "pub fn norm_safe(p: &mut Point) -> (::std::os::raw::c_int, f64) { \
+         let mut len = ::std::mem::MaybeUninit::<f64>::uninit(); \
+         let ret = unsafe { norm(p, len.as_mut_ptr().cast()) }; \
+         (ret, unsafe { len.assume_init() }) }",
+        "pub fn toggle_safe(on: bool) -> bool { \
+         unsafe { toggle(::std::os::raw::c_int::from(on)) != 0 } }",
+        "pub fn checked_inc_safe(x: ::std::os::raw::c_int) -> Option<::std::os::raw::c_int> { \
+         let mut result = ::std::mem::MaybeUninit::<::std::os::raw::c_int>::uninit(); \
+         let overflowed = unsafe { checked_inc(x, result.as_mut_ptr().cast()) }; \
+         (!overflowed).then(|| unsafe { result.assume_init() }) }",
+        "pub fn sum_avx2_safe(x: &[f32]) -> f32 { \
+         #[cfg(any(target_arch = \"x86\", target_arch = \"x86_64\"))] \
+         let detected = ::std::arch::is_x86_feature_detected!(\"avx2\"); \
+         #[cfg(not(any(target_arch = \"x86\", target_arch = \"x86_64\")))] \
+         let detected = false; \
+         assert!(detected, \"`sum_avx2` needs a CPU with `avx2`\"); \
+         unsafe { sum_avx2(x.as_ptr(), x.len()) } }",
+        "pub fn each_safe(mut f: impl FnMut(::std::os::raw::c_int)) {",
+        "pub fn each_catch_unwind(",
+        "pub fn each_c_unwind(",
+        "pub fn visit_catch_unwind(",
+    ];
+    for wrapper in expected {
+        assert!(wrappers.contains(wrapper), "no `{wrapper}` in:\n{wrappers}");
+    }
+    assert!(!wrappers.contains("unannotated_safe"));
+    // Without `@ir unwind`, C may have no unwind tables.
+    assert!(!wrappers.contains("visit_c_unwind"));
+    assert!(!wrappers.contains("series_sum_safe"));
+}
+
+#[test]
+#[should_panic(expected = "`broken`: bad annotation: unknown annotation `slcie`")]
+fn fails_on_a_malformed_annotation() {
+    let bindings = r#"
+pub mod kernels {
+unsafe extern "C" {
+    #[doc = " @ir slcie(a, len)"]
+    pub fn broken(a: *const f64, len: usize) -> f64;
+}
+}
+"#;
+    generate("fails_on_a_malformed_annotation", bindings);
+}
+
+#[test]
+#[should_panic(expected = "`broken`: annotation names unknown parameter `b`")]
+fn fails_on_an_annotation_that_does_not_fit() {
+    let bindings = r#"
+pub mod kernels {
+unsafe extern "C" {
+    #[doc = " @ir slice(b, len)"]
+    pub fn broken(a: *const f64, len: usize) -> f64;
+}
+}
+"#;
+    generate("fails_on_an_annotation_that_does_not_fit", bindings);
+}
+
+#[test]
+#[should_panic(
+    expected = "`broken`: `s` is annotated, but `Series` ends in a flexible array member"
+)]
+fn fails_on_a_reference_to_a_flexible_array_member() {
+    let bindings = r#"
+pub mod kernels {
+pub struct Series {
+    pub len: usize,
+    pub data: __IncompleteArrayField<f64>,
+}
+unsafe extern "C" {
+    #[doc = " @ir ref(s)"]
+    pub fn broken(s: *const Series) -> f64;
+}
+}
+"#;
+    generate("fails_on_a_reference_to_a_flexible_array_member", bindings);
+}
+
+#[test]
+#[should_panic(expected = "`pick`: callback `f` returns `Color`, which may not be valid zeroed")]
+fn fails_on_a_callback_without_a_zero_value() {
+    let bindings = r#"
+pub mod kernels {
+#[repr(u32)]
+pub enum Color {
+    Red = 1,
+}
+unsafe extern "C" {
+    #[doc = " @ir callback(f, context)"]
+    pub fn pick(
+        f: ::std::option::Option<unsafe extern "C" fn(context: *mut ::std::os::raw::c_void) -> Color>,
+        context: *mut ::std::os::raw::c_void,
+    );
+}
+}
+"#;
+    generate("fails_on_a_callback_without_a_zero_value", bindings);
+}
+
+#[test]
+#[should_panic(expected = "`broken`: `a` may unwind, but it isn't an @ir callback")]
+fn fails_on_unwind_without_a_callback() {
+    let bindings = r#"
+pub mod kernels {
+unsafe extern "C" {
+    #[doc = " @ir slice(a, len)\n @ir unwind(a)"]
+    pub fn broken(a: *const f64, len: usize) -> f64;
+}
+}
+"#;
+    generate("fails_on_unwind_without_a_callback", bindings);
+}
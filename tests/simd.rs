@@ -0,0 +1,58 @@
+//! Checks that the iterator and unrolled flavours of the SIMD kernels agree
+//! with the `std::arch` ones.
+#![cfg(target_arch = "x86_64")]
+
+use ir_comparison::native::simd;
+
+fn inputs() -> (Vec<f32>, Vec<u8>) {
+    let x = (0..1003).map(|i| (i as f32 * 0.37).sin()).collect();
+    let data = (0..1003u32).map(|i| (i * i % 251) as u8).collect();
+    (x, data)
+}
+
+#[test]
+fn saxpy_flavours_agree() {
+    let (x, _) = inputs();
+    let mut expected = x.clone();
+    simd::saxpy_sse2(0.75, &x, &mut expected);
+    let mut y = x.clone();
+    simd::saxpy_iter(0.75, &x, &mut y);
+    assert_eq!(y, expected, "saxpy_iter");
+    let mut y = x.clone();
+    simd::saxpy_unrolled(0.75, &x, &mut y);
+    assert_eq!(y, expected, "saxpy_unrolled");
+}
+
+#[test]
+fn sum_flavours_agree() {
+    let (x, _) = inputs();
+    let expected = simd::sum_sse2(&x);
+    // The flavours add in different orders, so only `sum_unrolled` and
+    // `sum_avx2`, which add in the same order, agree exactly.
+    assert!((simd::sum_iter(&x) - expected).abs() < 1e-3, "sum_iter");
+    assert!(
+        (simd::sum_unrolled(&x) - expected).abs() < 1e-3,
+        "sum_unrolled"
+    );
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: the CPU has AVX2.
+        let avx2 = unsafe { simd::sum_avx2(&x) };
+        assert_eq!(simd::sum_unrolled(&x), avx2, "sum_unrolled");
+    }
+}
+
+#[test]
+fn byte_histogram_flavours_agree() {
+    let (_, data) = inputs();
+    let expected = simd::byte_histogram_sse2(&data);
+    assert_eq!(
+        simd::byte_histogram_iter(&data),
+        expected,
+        "byte_histogram_iter"
+    );
+    assert_eq!(
+        simd::byte_histogram_unrolled(&data),
+        expected,
+        "byte_histogram_unrolled"
+    );
+}
@@ -0,0 +1,136 @@
+//! Checks the version parsing of the toolchain module on real `--version`
+//! output, from upstream LLVM releases and vendor builds.
+
+use ir_comparison::toolchain::{LlvmVersion, parse_rustc_llvm_version};
+
+fn version(major: u32, minor: u32, patch: u32) -> Option<LlvmVersion> {
+    Some(LlvmVersion {
+        major,
+        minor,
+        patch,
+    })
+}
+
+#[test]
+fn parses_rustc_llvm_version() {
+    let stable = "\
+rustc 1.90.0 (1159e78c4 2025-09-14)
+binary: rustc
+commit-hash: 1159e78c4747b02ef996e55082b704c09b970588
+commit-date: 2025-09-14
+host: x86_64-unknown-linux-gnu
+release: 1.90.0
+LLVM version: 20.1.8
+";
+    assert_eq!(parse_rustc_llvm_version(stable), version(20, 1, 8));
+    // Distribution builds against the system LLVM add its suffix.
+    let debian = "\
+rustc 1.63.0
+binary: rustc
+commit-hash: unknown
+commit-date: unknown
+host: x86_64-unknown-linux-gnu
+release: 1.63.0
+LLVM version: 14.0.6
+";
+    assert_eq!(parse_rustc_llvm_version(debian), version(14, 0, 6));
+    assert_eq!(
+        parse_rustc_llvm_version("rustc 1.90.0\nbinary: rustc\n"),
+        None
+    );
+}
+
+#[test]
+fn parses_dotted_versions() {
+    assert_eq!(LlvmVersion::parse("18.1.3"), version(18, 1, 3));
+    assert_eq!(LlvmVersion::parse("18.1.3-1ubuntu1"), version(18, 1, 3));
+    assert_eq!(LlvmVersion::parse("19.1.0git"), version(19, 1, 0));
+    assert_eq!(LlvmVersion::parse("17.0"), version(17, 0, 0));
+    assert_eq!(LlvmVersion::parse("20"), version(20, 0, 0));
+    assert_eq!(LlvmVersion::parse("trunk"), None);
+    assert_eq!(LlvmVersion::parse(""), None);
+}
+
+#[test]
+fn finds_the_version_in_clang_output() {
+    let upstream = "\
+clang version 20.1.8 (https://github.com/llvm/llvm-project 87f0227cb60147a26a1eeb4fb06e3b505e9c7261)
+Target: x86_64-unknown-linux-gnu
+Thread model: posix
+InstalledDir: /usr/local/bin
+";
+    assert_eq!(
+        LlvmVersion::from_version_output(upstream),
+        version(20, 1, 8)
+    );
+    let ubuntu = "\
+Ubuntu clang version 18.1.3 (1ubuntu1)
+Target: x86_64-pc-linux-gnu
+Thread model: posix
+InstalledDir: /usr/bin
+";
+    assert_eq!(LlvmVersion::from_version_output(ubuntu), version(18, 1, 3));
+    let debian = "\
+Debian clang version 14.0.6
+Target: x86_64-pc-linux-gnu
+Thread model: posix
+InstalledDir: /usr/bin
+";
+    assert_eq!(LlvmVersion::from_version_output(debian), version(14, 0, 6));
+    let homebrew = "\
+Homebrew clang version 19.1.7
+Target: arm64-apple-darwin24.3.0
+Thread model: posix
+InstalledDir: /opt/homebrew/Cellar/llvm/19.1.7_1/bin
+Configuration file: /opt/homebrew/etc/clang/arm64-apple-darwin24.cfg
+";
+    assert_eq!(
+        LlvmVersion::from_version_output(homebrew),
+        version(19, 1, 7)
+    );
+}
+
+#[test]
+fn finds_the_version_in_llvm_ar_output() {
+    let upstream = "\
+LLVM (http://llvm.org/):
+  LLVM version 20.1.8
+  Optimized build.
+";
+    assert_eq!(
+        LlvmVersion::from_version_output(upstream),
+        version(20, 1, 8)
+    );
+    let ubuntu = "\
+Ubuntu LLVM version 18.1.3
+  Optimized build.
+";
+    assert_eq!(LlvmVersion::from_version_output(ubuntu), version(18, 1, 3));
+}
+
+#[test]
+fn finds_the_version_in_lld_output() {
+    let upstream = "LLD 20.1.8 (compatible with GNU linkers)\n";
+    assert_eq!(
+        LlvmVersion::from_version_output(upstream),
+        version(20, 1, 8)
+    );
+    let debian = "Debian LLD 14.0.6 (compatible with GNU linkers)\n";
+    assert_eq!(LlvmVersion::from_version_output(debian), version(14, 0, 6));
+    let ubuntu = "Ubuntu LLD 18.1.3 (compatible with GNU linkers)\n";
+    assert_eq!(LlvmVersion::from_version_output(ubuntu), version(18, 1, 3));
+    let homebrew = "Homebrew LLD 19.1.7 (compatible with GNU linkers)\n";
+    assert_eq!(
+        LlvmVersion::from_version_output(homebrew),
+        version(19, 1, 7)
+    );
+}
+
+#[test]
+fn finds_no_version_in_other_output() {
+    assert_eq!(LlvmVersion::from_version_output(""), None);
+    assert_eq!(
+        LlvmVersion::from_version_output("GNU ld (GNU Binutils for Ubuntu) 2.42\n"),
+        None
+    );
+}
@@ -0,0 +1,55 @@
+//! Checks the transpiled `static inline` functions against the C shims.
+#![cfg(feature = "transpile-inline")]
+
+use ir_comparison::{c_ffi, transpiled};
+
+#[test]
+fn transpiled_input_matches_c() {
+    for x in [-1000, -1, 0, 1, 1000] {
+        assert_eq!(
+            transpiled::input::inc(x),
+            c_ffi::input::inc_safe(x),
+            "inc({x})"
+        );
+        assert_eq!(
+            transpiled::input::dec(x),
+            c_ffi::input::dec_safe(x),
+            "dec({x})"
+        );
+    }
+    // `inc(INT_MAX)` and `dec(INT_MIN)` are undefined in C; the transpiler
+    // wraps, as the `__builtin_*_overflow` variants define it.
+    assert_eq!(
+        transpiled::input::inc(i32::MAX),
+        c_ffi::input::wrapping_inc_safe(i32::MAX),
+        "inc(MAX)"
+    );
+    assert_eq!(
+        transpiled::input::dec(i32::MIN),
+        c_ffi::input::wrapping_dec_safe(i32::MIN),
+        "dec(MIN)"
+    );
+}
+
+/// Comparisons as conditions of `?:` in `clamp_int`, and as `int` values
+/// in `in_range`.
+#[test]
+fn transpiled_comparisons_match_c() {
+    let values = [i32::MIN, -7, -1, 0, 1, 7, i32::MAX];
+    for x in values {
+        for lo in values {
+            for hi in values {
+                assert_eq!(
+                    transpiled::macros::clamp_int(x, lo, hi),
+                    c_ffi::macros::clamp_int_safe(x, lo, hi),
+                    "clamp_int({x}, {lo}, {hi})"
+                );
+                assert_eq!(
+                    transpiled::macros::in_range(x, lo, hi),
+                    c_ffi::macros::in_range_safe(x, lo, hi),
+                    "in_range({x}, {lo}, {hi})"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Panics in closures passed to C callbacks, through the `_catch_unwind`
+//! and `_c_unwind` wrappers. (`_safe` would abort the test process.)
+
+use std::any::Any;
+
+use ir_comparison::c_ffi::callbacks::{
+    map_array_c_unwind, map_array_catch_unwind, sort_ints_c_unwind, sort_ints_catch_unwind,
+};
+
+fn message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast::<&str>()
+            .map(|message| message.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[test]
+fn catch_unwind_returns_the_panic() {
+    let mut values = [3, 1, 2];
+    let err = sort_ints_catch_unwind(&mut values, |_, _| panic!("in compare")).unwrap_err();
+    assert_eq!(message(err), "in compare");
+
+    let mut calls = 0;
+    let err = map_array_catch_unwind(&mut values, |x| {
+        calls += 1;
+        if calls == 2 {
+            panic!("at call {calls}");
+        }
+        x
+    })
+    .unwrap_err();
+    assert_eq!(message(err), "at call 2");
+    // The closure isn't called again after it panics.
+    assert_eq!(calls, 2);
+}
+
+#[test]
+fn c_unwind_returns_the_panic() {
+    let mut values = [3, 1, 2];
+    let err = sort_ints_c_unwind(&mut values, |_, _| panic!("in compare")).unwrap_err();
+    assert_eq!(message(err), "in compare");
+
+    let mut calls = 0;
+    let err = map_array_c_unwind(&mut values, |x| {
+        calls += 1;
+        if calls == 2 {
+            panic!("at call {calls}");
+        }
+        x
+    })
+    .unwrap_err();
+    assert_eq!(message(err), "at call 2");
+    // The panic unwound through `map_array`, so it stopped there.
+    assert_eq!(calls, 2);
+}
+
+#[test]
+fn variants_without_panics_return_ok() {
+    let mut caught = [5, -1, 3, 0];
+    let mut unwound = caught;
+    sort_ints_catch_unwind(&mut caught, |a, b| a - b).unwrap();
+    sort_ints_c_unwind(&mut unwound, |a, b| a - b).unwrap();
+    assert_eq!(caught, [-1, 0, 3, 5]);
+    assert_eq!(unwound, caught);
+
+    map_array_catch_unwind(&mut caught, |x| x * 10).unwrap();
+    map_array_c_unwind(&mut unwound, |x| x * 10).unwrap();
+    assert_eq!(caught, [-10, 0, 30, 50]);
+    assert_eq!(unwound, caught);
+}